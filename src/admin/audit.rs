@@ -0,0 +1,337 @@
+//! An append-only log of administrative and moderation actions
+//!
+//! Every entry contains the hash of the previous entry, and its own hash is
+//! computed over its contents and the previous hash. Changing, removing or
+//! reordering entries therefore breaks the chain, which is detected by
+//! `verify_chain`.
+
+use chrono::naive::NaiveDateTime;
+use crate::admin::requests::AdminRequest;
+use crate::content::requests::ContentRequest;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+use crate::valid::network::IpNetwork;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt::{self, Display};
+use std::net::IpAddr;
+
+/// An action which is recorded in the audit log
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    BanIp,
+    UnbanIp,
+    SetUserRole,
+    ResolveReport,
+    DismissReport,
+    BanUser,
+    UnbanUser,
+    SuspendUser,
+    UnsuspendUser,
+    MuteUser,
+    UnmuteUser,
+    BulkHideUserContent,
+    BulkHideContent,
+    BulkMoveThreads,
+    SetRegistrationMode,
+    CreateInvite,
+    RevokeInvite,
+    ApproveUser,
+    RejectUser,
+
+    AddCategory,
+    EditCategory,
+    HideCategory,
+    MoveCategory,
+    ReorderCategories,
+    HideThread,
+    PinThread,
+    LockThread,
+    ArchiveThread,
+    MoveThread,
+    HideComment,
+}
+
+impl AuditAction {
+    /// Get the action which is recorded for an admin request, if any
+    ///
+    /// Dry runs and requests which only read (e.g. statistics or pending
+    /// users) don't change anything, so they are not recorded.
+    pub fn from_admin_request(request: &AdminRequest) -> Option<AuditAction> {
+        use crate::admin::requests::AdminRequest::*;
+        if request.is_dry_run() {
+            return None;
+        }
+        let action = match request {
+            BanIp(_) => AuditAction::BanIp,
+            UnbanIp(_) => AuditAction::UnbanIp,
+            SetUserRole(_) => AuditAction::SetUserRole,
+            ResolveReport(_) => AuditAction::ResolveReport,
+            DismissReport(_) => AuditAction::DismissReport,
+            BanUser(_) => AuditAction::BanUser,
+            UnbanUser(_) => AuditAction::UnbanUser,
+            SuspendUser(_) => AuditAction::SuspendUser,
+            UnsuspendUser(_) => AuditAction::UnsuspendUser,
+            MuteUser(_) => AuditAction::MuteUser,
+            UnmuteUser(_) => AuditAction::UnmuteUser,
+            BulkHideUserContent(_) => AuditAction::BulkHideUserContent,
+            BulkHideContent(_) => AuditAction::BulkHideContent,
+            BulkMoveThreads(_) => AuditAction::BulkMoveThreads,
+            GetStats(_) => return None,
+            SetRegistrationMode(_) => AuditAction::SetRegistrationMode,
+            CreateInvite(_) => AuditAction::CreateInvite,
+            RevokeInvite(_) => AuditAction::RevokeInvite,
+            GetInvites(_) => return None,
+            GetPendingUsers(_) => return None,
+            ApproveUser(_) => AuditAction::ApproveUser,
+            RejectUser(_) => AuditAction::RejectUser,
+        };
+        Some(action)
+    }
+
+    /// Get the action which is recorded for a content request, if any
+    ///
+    /// Only the requests which are reserved for moderators are recorded.
+    pub fn from_content_request(request: &ContentRequest) -> Option<AuditAction> {
+        use crate::content::requests::ContentRequest::*;
+        match request {
+            AddCategory(_) => Some(AuditAction::AddCategory),
+            EditCategory(_) => Some(AuditAction::EditCategory),
+            HideCategory(_) => Some(AuditAction::HideCategory),
+            MoveCategory(_) => Some(AuditAction::MoveCategory),
+            ReorderCategories(_) => Some(AuditAction::ReorderCategories),
+            HideThread(_) => Some(AuditAction::HideThread),
+            PinThread(_) => Some(AuditAction::PinThread),
+            LockThread(_) => Some(AuditAction::LockThread),
+            ArchiveThread(_) => Some(AuditAction::ArchiveThread),
+            MoveThread(_) => Some(AuditAction::MoveThread),
+            HideComment(_) => Some(AuditAction::HideComment),
+            _ => None,
+        }
+    }
+}
+
+/// What an audited action was performed on
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(
+    tag = "type",
+    content = "id",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum AuditTarget {
+    User(UserId),
+    Content(ContentRef),
+    Category(CategoryId),
+    IpNetwork(IpNetwork),
+    Report(ReportId),
+}
+
+/// A SHA-256 hash which links an entry to the previous entry of the log
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct AuditHash([u8; 32]);
+
+impl AuditHash {
+    /// The hash which the first entry of the log is linked to
+    pub const GENESIS: AuditHash = AuditHash([0; 32]);
+}
+
+impl Display for AuditHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.0.iter() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl serde::Serialize for AuditHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::de::Deserialize<'de> for AuditHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        let s = String::deserialize(deserializer)?;
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(D::Error::custom("invalid audit hash"));
+        }
+        let mut hash = [0; 32];
+        for (i, b) in hash.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)
+                .map_err(|_| D::Error::custom("invalid audit hash"))?;
+        }
+        Ok(AuditHash(hash))
+    }
+}
+
+/// The contents of an entry in the audit log, before it is linked into the
+/// chain
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AuditRecord {
+    pub id: AuditEntryId,
+    pub actor: UserId,
+    pub action: AuditAction,
+    pub target: Option<AuditTarget>,
+    pub reason: Option<Reason>,
+    /// A snapshot of the target before the action
+    pub before: Option<Value>,
+    /// A snapshot of the target after the action
+    pub after: Option<Value>,
+    pub timestamp: NaiveDateTime,
+    /// The address the action was performed from
+    pub ip: Option<IpAddr>,
+}
+
+impl AuditRecord {
+    /// Link the record to the previous entry in the log
+    pub fn seal(self, previous_hash: AuditHash) -> AuditEntry {
+        let hash = hash_record(&self, previous_hash);
+        AuditEntry {
+            record: self,
+            previous_hash,
+            hash,
+        }
+    }
+}
+
+/// An entry in the audit log
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AuditEntry {
+    #[serde(flatten)]
+    pub record: AuditRecord,
+    pub previous_hash: AuditHash,
+    pub hash: AuditHash,
+}
+
+impl AuditEntry {
+    /// Check that the hash of the entry matches its contents
+    pub fn is_intact(&self) -> bool {
+        hash_record(&self.record, self.previous_hash) == self.hash
+    }
+}
+
+/// Hash the record as it is stored, i.e. its serialized form, which every
+/// field (including the reason, as it was typed) reproduces exactly when the
+/// record is read back
+fn hash_record(record: &AuditRecord, previous_hash: AuditHash) -> AuditHash {
+    let contents = serde_json::to_vec(record).expect("audit record is always serializable");
+    let mut hasher = Sha256::new();
+    hasher.input(previous_hash.0);
+    hasher.input(&contents);
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&hasher.result());
+    AuditHash(hash)
+}
+
+/// The audit log has been tampered with
+#[derive(Fail, PartialEq, Clone, Copy, Debug)]
+#[fail(display = "audit log is broken at entry {}", index)]
+pub struct BrokenChain {
+    /// The index of the first entry which does not match the chain
+    pub index: usize,
+}
+
+/// Verify that the entries form an unbroken chain, starting from the entry
+/// with the hash `previous_hash` (`AuditHash::GENESIS` for the full log)
+pub fn verify_chain(previous_hash: AuditHash, entries: &[AuditEntry]) -> Result<(), BrokenChain> {
+    let mut previous_hash = previous_hash;
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.previous_hash != previous_hash || !entry.is_intact() {
+            return Err(BrokenChain { index });
+        }
+        previous_hash = entry.hash;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use std::convert::TryFrom;
+
+    fn chain(n: u32) -> Vec<AuditEntry> {
+        let mut entries: Vec<AuditEntry> = Vec::new();
+        for i in 0..n {
+            let record = AuditRecord {
+                id: AuditEntryId::from(i),
+                actor: UserId::from(1),
+                action: AuditAction::BanUser,
+                target: Some(AuditTarget::User(UserId::from(100 + i))),
+                reason: if i % 2 == 0 {
+                    None
+                } else {
+                    Some(Reason::try_from("user's spam &lt; & <eggs>".to_owned()).unwrap())
+                },
+                before: None,
+                after: Some(Value::Bool(true)),
+                timestamp: NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, i),
+                ip: Some("127.0.0.1".parse().unwrap()),
+            };
+            let previous_hash = entries.last().map(|e| e.hash).unwrap_or(AuditHash::GENESIS);
+            entries.push(record.seal(previous_hash));
+        }
+        entries
+    }
+
+    #[test]
+    fn verifies_unbroken_chain() {
+        let entries = chain(3);
+        assert_eq!(verify_chain(AuditHash::GENESIS, &entries), Ok(()));
+        assert_eq!(verify_chain(entries[0].hash, &entries[1..]), Ok(()));
+    }
+
+    #[test]
+    fn detects_tampering() {
+        let mut entries = chain(3);
+        entries[1].record.actor = UserId::from(2);
+        assert_eq!(
+            verify_chain(AuditHash::GENESIS, &entries),
+            Err(BrokenChain { index: 1 })
+        );
+
+        let mut entries = chain(3);
+        entries.remove(1);
+        assert_eq!(
+            verify_chain(AuditHash::GENESIS, &entries),
+            Err(BrokenChain { index: 1 })
+        );
+    }
+
+    #[test]
+    fn hash_survives_serialization() {
+        let entries = chain(2);
+        let json = serde_json::to_string(&entries).unwrap();
+        let parsed: Vec<AuditEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, entries);
+        assert_eq!(verify_chain(AuditHash::GENESIS, &parsed), Ok(()));
+        let reason = parsed[1].record.reason.as_ref().unwrap();
+        assert_eq!(&**reason, "user's spam &lt; & <eggs>");
+
+        // Storing and loading the entries again must not change them either
+        let json = serde_json::to_string(&parsed).unwrap();
+        let parsed: Vec<AuditEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_chain(AuditHash::GENESIS, &parsed), Ok(()));
+    }
+
+    #[test]
+    fn read_requests_are_not_recorded() {
+        let requests = [
+            r#"{ "type": "GET_INVITES", "payload": { "include_unusable": true } }"#,
+            r#"{ "type": "GET_PENDING_USERS", "payload": { "offset": 25 } }"#,
+        ];
+        for json in requests.iter() {
+            let request: AdminRequest = serde_json::from_str(json).unwrap();
+            assert_eq!(AuditAction::from_admin_request(&request), None);
+        }
+    }
+}
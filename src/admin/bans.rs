@@ -0,0 +1,188 @@
+//! IP bans and an efficient lookup of the bans which match an address
+
+use chrono::naive::NaiveDateTime;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+use crate::valid::network::{self, IpNetwork};
+use std::net::IpAddr;
+
+/// A ban of all the addresses in a network
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct IpBanPayload {
+    pub network: IpNetwork,
+    pub reason: Reason,
+    pub issued_by: UserId,
+    pub issued_at: NaiveDateTime,
+    /// The time the ban expires, `None` if it is permanent
+    pub until: Option<NaiveDateTime>,
+}
+
+impl IpBanPayload {
+    /// Check if the ban is in effect at the given time
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.until.map(|until| now < until).unwrap_or(true)
+    }
+}
+
+/// A node in a binary trie over the bits of the banned networks
+#[derive(Default, Debug)]
+struct Node {
+    children: [Option<Box<Node>>; 2],
+    /// Indices of the bans of the network which ends at this node
+    bans: Vec<usize>,
+}
+
+/// A list of IP bans which answers if an address is banned
+///
+/// The networks are stored in a prefix trie (one for IPv4 and one for IPv6),
+/// so a lookup only visits as many nodes as there are bits in an address,
+/// independent of the number of bans.
+#[derive(Default, Debug)]
+pub struct IpBanList {
+    bans: Vec<IpBanPayload>,
+    v4: Node,
+    v6: Node,
+}
+
+impl IpBanList {
+    pub fn new() -> Self {
+        IpBanList::default()
+    }
+
+    /// Add a ban to the list
+    pub fn insert(&mut self, ban: IpBanPayload) {
+        let index = self.bans.len();
+        let network = ban.network;
+        let mut node = match network.addr() {
+            IpAddr::V4(_) => &mut self.v4,
+            IpAddr::V6(_) => &mut self.v6,
+        };
+        for i in 0..network.prefix() {
+            let child = &mut node.children[network.bit(i) as usize];
+            node = child.get_or_insert_with(Default::default);
+        }
+        node.bans.push(index);
+        self.bans.push(ban);
+    }
+
+    /// Find a ban which is in effect for the address at the given time
+    ///
+    /// If several bans match, the ban of the largest network is returned.
+    /// IPv4-mapped IPv6 addresses are looked up as the IPv4 address they
+    /// represent.
+    pub fn find(&self, addr: IpAddr, now: NaiveDateTime) -> Option<&IpBanPayload> {
+        let addr = network::canonical(addr);
+        let (mut node, width) = match addr {
+            IpAddr::V4(_) => (&self.v4, 32),
+            IpAddr::V6(_) => (&self.v6, 128),
+        };
+        let mut depth = 0;
+        loop {
+            let active = node
+                .bans
+                .iter()
+                .map(|i| &self.bans[*i])
+                .find(|ban| ban.is_active(now));
+            if active.is_some() {
+                return active;
+            }
+            if depth == width {
+                return None;
+            }
+            node = node.children[network::bit(addr, depth) as usize].as_ref()?;
+            depth += 1;
+        }
+    }
+
+    /// Check if the address is banned at the given time
+    pub fn is_banned(&self, addr: IpAddr, now: NaiveDateTime) -> bool {
+        self.find(addr, now).is_some()
+    }
+
+    /// Get all the bans in the list, in the order they were inserted
+    pub fn bans(&self) -> &[IpBanPayload] {
+        &self.bans
+    }
+
+    pub fn len(&self) -> usize {
+        self.bans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bans.is_empty()
+    }
+}
+
+impl std::iter::FromIterator<IpBanPayload> for IpBanList {
+    fn from_iter<I: IntoIterator<Item = IpBanPayload>>(iter: I) -> Self {
+        let mut list = IpBanList::new();
+        for ban in iter {
+            list.insert(ban);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use std::convert::TryFrom;
+
+    fn ban(network: &str, until: Option<NaiveDateTime>) -> IpBanPayload {
+        IpBanPayload {
+            network: IpNetwork::try_from(network).unwrap(),
+            reason: Reason::try_from("Spam".to_owned()).unwrap(),
+            issued_by: UserId::from(1),
+            issued_at: NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0),
+            until,
+        }
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn finds_matching_bans() {
+        let now = NaiveDate::from_ymd(2018, 10, 2).and_hms(12, 0, 0);
+        let expired = NaiveDate::from_ymd(2018, 10, 1).and_hms(18, 0, 0);
+        let list: IpBanList = vec![
+            ban("10.0.0.0/8", None),
+            ban("192.168.1.7", None),
+            ban("172.16.0.0/12", Some(expired)),
+            ban("2001:db8::/32", None),
+        ]
+        .into_iter()
+        .collect();
+
+        assert!(list.is_banned(ip("10.20.30.40"), now));
+        assert!(list.is_banned(ip("192.168.1.7"), now));
+        assert!(!list.is_banned(ip("192.168.1.8"), now));
+        assert!(!list.is_banned(ip("172.16.0.1"), now));
+        assert!(list.is_banned(ip("172.16.0.1"), expired - chrono::Duration::hours(1)));
+        assert!(list.is_banned(ip("2001:db8::1"), now));
+        assert!(!list.is_banned(ip("::1"), now));
+    }
+
+    #[test]
+    fn finds_bans_of_mapped_addresses() {
+        let now = NaiveDate::from_ymd(2018, 10, 2).and_hms(12, 0, 0);
+        let list: IpBanList = vec![ban("10.0.0.0/8", None), ban("::ffff:192.168.0.0/120", None)]
+            .into_iter()
+            .collect();
+        assert!(list.is_banned(ip("::ffff:10.0.0.1"), now));
+        assert!(list.is_banned(ip("192.168.0.1"), now));
+        assert!(list.is_banned(ip("::ffff:192.168.0.1"), now));
+        assert!(!list.is_banned(ip("::ffff:11.0.0.1"), now));
+        assert!(!list.is_banned(ip("::10.0.0.1"), now));
+    }
+
+    #[test]
+    fn bans_everything() {
+        let now = NaiveDate::from_ymd(2018, 10, 2).and_hms(12, 0, 0);
+        let list: IpBanList = vec![ban("0.0.0.0/0", None)].into_iter().collect();
+        assert!(list.is_banned(ip("8.8.8.8"), now));
+        assert!(!list.is_banned(ip("2001:db8::1"), now));
+    }
+}
@@ -1,4 +1,8 @@
 //! Admin requests and responses to and from the service
 
+pub mod audit;
+pub mod bans;
 pub mod requests;
 pub mod responses;
+pub mod safeguards;
+pub mod stats;
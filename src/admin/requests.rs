@@ -1,6 +1,18 @@
 //! The requests a admin can send to the service
 
+use chrono::naive::NaiveDateTime;
+use crate::admin::audit::{AuditAction, AuditTarget};
+use crate::admin::stats::StatsBucket;
+use crate::auth::registration::RegistrationMode;
 use crate::auth::requests::SetUserRolePayload;
+use crate::payloads::PagePayload;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+use crate::valid::network::IpNetwork;
+use crate::valid::ValidationError;
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use std::fmt;
+use std::marker::PhantomData;
 use std::net::IpAddr;
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -10,12 +22,346 @@ use std::net::IpAddr;
     rename_all = "SCREAMING_SNAKE_CASE"
 )]
 pub enum AdminRequest {
-    BanIp(IpAddrPayload),
-    UnbanIp(IpAddrPayload),
+    BanIp(BanIpPayload),
+    UnbanIp(UnbanIpPayload),
     SetUserRole(SetUserRolePayload),
+    ResolveReport(ResolveReportPayload),
+    DismissReport(DismissReportPayload),
+
+    BanUser(SanctionUserPayload),
+    UnbanUser(LiftSanctionPayload),
+    SuspendUser(SuspendUserPayload),
+    UnsuspendUser(LiftSanctionPayload),
+    MuteUser(MuteUserPayload),
+    UnmuteUser(UnmuteUserPayload),
+
+    BulkHideUserContent(BulkHideUserContentPayload),
+    BulkHideContent(BulkHideContentPayload),
+    BulkMoveThreads(BulkMoveThreadsPayload),
+
+    GetStats(GetStatsPayload),
+
+    SetRegistrationMode(RegistrationMode),
+    CreateInvite(CreateInvitePayload),
+    RevokeInvite(InviteCodePayload),
+    GetInvites(GetInvitesPayload),
+    GetPendingUsers(GetPendingUsersPayload),
+    ApproveUser(ApproveUserPayload),
+    RejectUser(RejectUserPayload),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct IpAddrPayload {
-    pub ip: IpAddr,
+/// Ban all the addresses in a network, until the given time or permanently
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BanIpPayload {
+    pub network: IpNetwork,
+    pub until: Option<NaiveDateTime>,
+    pub reason: Reason,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct UnbanIpPayload {
+    pub network: IpNetwork,
+    pub reason: Reason,
+}
+
+/// Get a page of the IP bans, optionally only those which match `containing`
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetIpBansPayload {
+    pub containing: Option<IpAddr>,
+    pub include_expired: bool,
+    #[serde(flatten)]
+    pub page: PagePayload,
+}
+
+/// An action taken by a moderator as part of resolving a report
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum ModerationAction {
+    /// Hide the reported thread or comment
+    HideContent,
+    BanIp(BanIpPayload),
+    /// Ban the author of the reported thread or comment
+    BanAuthor,
+    /// Suspend the author of the reported thread or comment
+    SuspendAuthor(SuspendAuthorPayload),
+    /// Mute the author of the reported thread or comment
+    MuteAuthor(MuteAuthorPayload),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SuspendAuthorPayload {
+    pub until: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MuteAuthorPayload {
+    pub scope: MuteScope,
+    pub until: Option<NaiveDateTime>,
+}
+
+/// Resolve a report by performing the given actions
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ResolveReportPayload {
+    pub id: ReportId,
+    pub reason: Reason,
+    pub actions: Vec<ModerationAction>,
+}
+
+/// Dismiss a report without performing any actions
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct DismissReportPayload {
+    pub id: ReportId,
+    pub reason: Reason,
+}
+
+// Audit log
+
+/// Get a page of the audit log, newest first, filtered by the fields which
+/// are present
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetAuditLogPayload {
+    pub actor: Option<UserId>,
+    pub action: Option<AuditAction>,
+    pub target: Option<AuditTarget>,
+    pub since: Option<NaiveDateTime>,
+    pub until: Option<NaiveDateTime>,
+    #[serde(flatten)]
+    pub page: PagePayload,
+}
+
+// Statistics
+
+/// Get the activity in `since..until` grouped by `bucket`, with the `top`
+/// most active categories and users
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetStatsPayload {
+    pub bucket: StatsBucket,
+    pub since: NaiveDateTime,
+    pub until: NaiveDateTime,
+    #[serde(default = "GetStatsPayload::default_top")]
+    pub top: u32,
+}
+
+impl GetStatsPayload {
+    fn default_top() -> u32 {
+        10
+    }
+}
+
+// Registration
+
+/// Create an invite code, which expires at the given time or never, and can
+/// be used `max_uses` times or an unlimited number of times
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct CreateInvitePayload {
+    pub expires: Option<NaiveDateTime>,
+    pub max_uses: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct InviteCodePayload {
+    pub code: InviteCode,
+}
+
+/// Get a page of the invites, optionally including revoked and expired ones
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetInvitesPayload {
+    pub include_unusable: bool,
+    #[serde(flatten)]
+    pub page: PagePayload,
+}
+
+/// Get a page of the accounts awaiting approval, oldest first
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetPendingUsersPayload {
+    #[serde(flatten)]
+    pub page: PagePayload,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ApproveUserPayload {
+    pub id: UserId,
+}
+
+/// Reject a pending account, the reason is sent to the user
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct RejectUserPayload {
+    pub id: UserId,
+    pub reason: Reason,
+}
+
+// Sanctions
+
+/// Where a muted user is prevented from posting
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(
+    tag = "type",
+    content = "id",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum MuteScope {
+    Everywhere,
+    Category(CategoryId),
+    Thread(ThreadId),
+    /// Private messages
+    Messages,
+}
+
+/// Ban a user permanently
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SanctionUserPayload {
+    pub id: UserId,
+    pub reason: Reason,
+}
+
+/// Suspend a user until the given time
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SuspendUserPayload {
+    pub id: UserId,
+    pub until: NaiveDateTime,
+    pub reason: Reason,
+}
+
+/// Mute a user within a scope, until the given time or permanently
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MuteUserPayload {
+    pub id: UserId,
+    pub scope: MuteScope,
+    pub until: Option<NaiveDateTime>,
+    pub reason: Reason,
+}
+
+/// Lift the ban or suspension of a user
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct LiftSanctionPayload {
+    pub id: UserId,
+    pub reason: Reason,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct UnmuteUserPayload {
+    pub id: UserId,
+    pub scope: MuteScope,
+    pub reason: Reason,
+}
+
+// Bulk moderation
+
+/// The maximum number of items a single bulk request may list
+pub const MAX_BULK_ITEMS: usize = 500;
+
+/// Deserialize the items of a bulk request, which is refused as soon as it
+/// lists more than `MAX_BULK_ITEMS` items
+///
+/// Intended to be used with `#[serde(deserialize_with = "...")]`
+pub fn deserialize_bulk_items<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct BulkItems<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for BulkItems<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a list of at most {} items", MAX_BULK_ITEMS)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut items = Vec::new();
+            while let Some(item) = seq.next_element()? {
+                if items.len() == MAX_BULK_ITEMS {
+                    return Err(serde::de::Error::custom(ValidationError::TooManyBulkItems));
+                }
+                items.push(item);
+            }
+            Ok(items)
+        }
+    }
+
+    deserializer.deserialize_seq(BulkItems(PhantomData))
+}
+
+/// Hide (or unhide) all threads and comments a user has posted in a time
+/// window, the window is open ended if `since` or `until` is missing
+///
+/// With `dry_run` nothing is changed, but the result reports what would have
+/// been affected.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BulkHideUserContentPayload {
+    pub user_id: UserId,
+    pub since: Option<NaiveDateTime>,
+    pub until: Option<NaiveDateTime>,
+    pub hide: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub reason: Reason,
+}
+
+/// Hide (or unhide) a list of threads and comments
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BulkHideContentPayload {
+    #[serde(deserialize_with = "deserialize_bulk_items")]
+    pub targets: Vec<ContentRef>,
+    pub hide: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub reason: Reason,
+}
+
+/// Move a list of threads to another category
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BulkMoveThreadsPayload {
+    #[serde(deserialize_with = "deserialize_bulk_items")]
+    pub ids: Vec<ThreadId>,
+    pub category_id: CategoryId,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub reason: Reason,
+}
+
+impl AdminRequest {
+    /// Check if the request only reports what it would change
+    pub fn is_dry_run(&self) -> bool {
+        use self::AdminRequest::*;
+        match self {
+            BulkHideUserContent(payload) => payload.dry_run,
+            BulkHideContent(payload) => payload.dry_run,
+            BulkMoveThreads(payload) => payload.dry_run,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn move_threads(n: u32) -> Result<BulkMoveThreadsPayload, serde_json::Error> {
+        let ids: Vec<_> = (0..n).collect();
+        let json = format!(
+            r#"{{ "ids": {:?}, "category_id": 1, "reason": "cleanup" }}"#,
+            ids
+        );
+        serde_json::from_str(&json)
+    }
+
+    #[test]
+    fn limits_bulk_items() {
+        assert_eq!(move_threads(MAX_BULK_ITEMS as u32).unwrap().ids.len(), MAX_BULK_ITEMS);
+        let err = move_threads(MAX_BULK_ITEMS as u32 + 1).unwrap_err();
+        assert!(err.to_string().contains("too many items"));
+        assert!(move_threads(100_000).is_err());
+    }
 }
@@ -1,5 +1,16 @@
 //! The responses a admin will get from requests to the service
 
+use chrono::naive::NaiveDateTime;
+use crate::admin::audit::AuditEntry;
+use crate::admin::bans::IpBanPayload;
+use crate::admin::requests::MuteScope;
+use crate::admin::stats::StatsPayload;
+use crate::auth::registration::{InvitePayload, PendingUserPayload};
+use crate::auth::responses::AuthError;
+use crate::content::responses::ContentError;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(
     tag = "type",
@@ -9,7 +20,69 @@
 pub enum AdminSuccess {
     IpBanned,
     IpUnbanned,
+    IpBans(Vec<IpBanPayload>),
     ChangedRole,
+    ReportResolved,
+    ReportDismissed,
+    Sanction(SanctionPayload),
+    SanctionLifted,
+    AuditLog(AuditLogPayload),
+    BulkResult(BulkResultPayload),
+    Stats(StatsPayload),
+    RegistrationModeSet,
+    InviteCreated(InvitePayload),
+    InviteRevoked,
+    Invites(Vec<InvitePayload>),
+    PendingUsers(Vec<PendingUserPayload>),
+    UserApproved,
+    UserRejected,
+}
+
+/// A page of the audit log, newest first
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct AuditLogPayload {
+    pub entries: Vec<AuditEntry>,
+    pub total: u32,
+}
+
+/// What happened (or would happen in a dry run) to a single item of a bulk
+/// request
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BulkOutcome {
+    /// The item was changed (or would be changed)
+    Applied,
+    /// The item was already in the requested state
+    Unchanged,
+    /// The item does not exist
+    Missing,
+    /// The item could not be changed
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BulkItemResult {
+    pub target: ContentRef,
+    pub outcome: BulkOutcome,
+}
+
+/// The per item report of a bulk request
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BulkResultPayload {
+    pub dry_run: bool,
+    pub items: Vec<BulkItemResult>,
+}
+
+impl BulkResultPayload {
+    /// The number of items which were (or would be) changed
+    pub fn applied(&self) -> usize {
+        self.count(BulkOutcome::Applied)
+    }
+
+    /// The number of items with the given outcome
+    pub fn count(&self, outcome: BulkOutcome) -> usize {
+        self.items.iter().filter(|i| i.outcome == outcome).count()
+    }
 }
 
 #[derive(Fail, Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
@@ -19,6 +92,181 @@ pub enum AdminSuccess {
     rename_all = "SCREAMING_SNAKE_CASE"
 )]
 pub enum AdminError {
+    #[fail(display = "user not found")]
+    UserNotFound,
+    #[fail(display = "category not found")]
+    CategoryNotFound,
+    #[fail(display = "report not found")]
+    ReportNotFound,
+    #[fail(display = "report is already resolved or dismissed")]
+    ReportAlreadyClosed,
+    #[fail(display = "cannot change the role of oneself")]
+    CannotChangeOwnRole,
+    #[fail(display = "cannot demote the last admin")]
+    LastAdmin,
+    #[fail(display = "cannot sanction oneself")]
+    CannotSanctionSelf,
+    #[fail(display = "cannot sanction a user with an equal or higher role")]
+    CannotSanctionPrivileged,
+    #[fail(display = "cannot ban a network containing ones own address")]
+    CannotBanOwnIp,
+    #[fail(display = "address is already banned")]
+    IpAlreadyBanned,
+    #[fail(display = "address is not banned")]
+    IpNotBanned,
+    #[fail(display = "user is already sanctioned")]
+    AlreadySanctioned,
+    #[fail(display = "user has no active sanction")]
+    NoActiveSanction,
+    #[fail(display = "sanction must end in the future")]
+    InvalidSanctionPeriod,
+    #[fail(display = "start of time range must be before its end")]
+    InvalidTimeRange,
+    #[fail(display = "too many items, at most {} are allowed", max)]
+    TooManyItems { max: u32 },
+    #[fail(display = "invite not found")]
+    InviteNotFound,
+    #[fail(display = "user is not awaiting approval")]
+    UserNotPending,
     #[fail(display = "internal occured error")]
     InternalError,
 }
+
+/// The kind of a sanction against a user
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(
+    tag = "type",
+    content = "scope",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum SanctionKind {
+    Ban,
+    Suspension,
+    Mute(MuteScope),
+}
+
+/// A ban, suspension or mute of a user
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SanctionPayload {
+    pub id: SanctionId,
+    pub user_id: UserId,
+    pub kind: SanctionKind,
+    pub reason: Reason,
+    pub issued_by: UserId,
+    pub issued_at: NaiveDateTime,
+    /// The time the sanction expires, `None` if it is permanent
+    pub until: Option<NaiveDateTime>,
+    pub lifted: bool,
+}
+
+impl SanctionPayload {
+    /// Check if the sanction is in effect at the given time
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        !self.lifted && self.until.map(|until| now < until).unwrap_or(true)
+    }
+
+    /// The error to give a user who tries to authenticate, if the sanction
+    /// prevents it at the given time
+    pub fn auth_error(&self, now: NaiveDateTime) -> Option<AuthError> {
+        if !self.is_active(now) {
+            return None;
+        }
+        match (self.kind, self.until) {
+            (SanctionKind::Ban, _) | (SanctionKind::Suspension, None) => Some(AuthError::Banned),
+            (SanctionKind::Suspension, Some(until)) => Some(AuthError::Suspended { until }),
+            (SanctionKind::Mute(_), _) => None,
+        }
+    }
+
+    /// The error to give a user who tries to post content, if the sanction
+    /// prevents it at the given time
+    ///
+    /// The scope of a mute is not taken into account, it is up to the caller
+    /// to only check the sanctions which apply to where the content is posted.
+    pub fn content_error(&self, now: NaiveDateTime) -> Option<ContentError> {
+        if !self.is_active(now) {
+            return None;
+        }
+        match (self.kind, self.until) {
+            (SanctionKind::Ban, _) | (SanctionKind::Suspension, None) => Some(ContentError::Banned),
+            (SanctionKind::Suspension, Some(until)) => Some(ContentError::Suspended { until }),
+            (SanctionKind::Mute(_), until) => Some(ContentError::Muted { until }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use std::convert::TryFrom;
+
+    fn time(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(2018, 10, day).and_hms(12, 0, 0)
+    }
+
+    fn sanction(kind: SanctionKind, until: Option<NaiveDateTime>, lifted: bool) -> SanctionPayload {
+        SanctionPayload {
+            id: SanctionId::from(1),
+            user_id: UserId::from(2),
+            kind,
+            reason: Reason::try_from("spam".to_owned()).unwrap(),
+            issued_by: UserId::from(1),
+            issued_at: time(1),
+            until,
+            lifted,
+        }
+    }
+
+    #[test]
+    fn expired_suspension_is_inactive() {
+        let suspension = sanction(SanctionKind::Suspension, Some(time(3)), false);
+        assert!(suspension.is_active(time(2)));
+        assert_eq!(
+            suspension.auth_error(time(2)),
+            Some(AuthError::Suspended { until: time(3) })
+        );
+        assert_eq!(
+            suspension.content_error(time(2)),
+            Some(ContentError::Suspended { until: time(3) })
+        );
+        assert!(!suspension.is_active(time(3)));
+        assert_eq!(suspension.auth_error(time(4)), None);
+        assert_eq!(suspension.content_error(time(4)), None);
+    }
+
+    #[test]
+    fn lifted_ban_is_inactive() {
+        let ban = sanction(SanctionKind::Ban, None, true);
+        assert!(!ban.is_active(time(2)));
+        assert_eq!(ban.auth_error(time(2)), None);
+        assert_eq!(ban.content_error(time(2)), None);
+    }
+
+    #[test]
+    fn permanent_ban_never_expires() {
+        let ban = sanction(SanctionKind::Ban, None, false);
+        let far_future = NaiveDate::from_ymd(2100, 1, 1).and_hms(0, 0, 0);
+        assert!(ban.is_active(far_future));
+        assert_eq!(ban.auth_error(far_future), Some(AuthError::Banned));
+        assert_eq!(ban.content_error(far_future), Some(ContentError::Banned));
+    }
+
+    #[test]
+    fn scoped_mute_only_prevents_posting() {
+        let scope = MuteScope::Thread(ThreadId::from(7));
+        let mute = sanction(SanctionKind::Mute(scope), Some(time(3)), false);
+        assert_eq!(mute.auth_error(time(2)), None);
+        assert_eq!(
+            mute.content_error(time(2)),
+            Some(ContentError::Muted {
+                until: Some(time(3))
+            })
+        );
+        let mute = sanction(SanctionKind::Mute(MuteScope::Everywhere), None, false);
+        assert_eq!(
+            mute.content_error(time(2)),
+            Some(ContentError::Muted { until: None })
+        );
+    }
+}
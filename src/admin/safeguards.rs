@@ -0,0 +1,205 @@
+//! Checks which protect the forum from admin mistakes, such as locking out
+//! every admin or banning oneself
+
+use chrono::naive::NaiveDateTime;
+use crate::admin::requests::MAX_BULK_ITEMS;
+use crate::admin::responses::AdminError;
+use crate::admin::stats::{StatsBucket, MAX_STATS_BUCKETS};
+use crate::auth::responses::Role;
+use crate::valid::ids::UserId;
+use crate::valid::network::IpNetwork;
+use std::net::IpAddr;
+
+/// Check that `actor` may change the role of `target` from `current` to
+/// `new`, where `admins` is the number of admins including `target`
+pub fn check_role_change(
+    actor: UserId,
+    target: UserId,
+    current: Role,
+    new: Role,
+    admins: u32,
+) -> Result<(), AdminError> {
+    if actor == target {
+        return Err(AdminError::CannotChangeOwnRole);
+    }
+    if current == Role::Admin && new != Role::Admin && admins <= 1 {
+        return Err(AdminError::LastAdmin);
+    }
+    Ok(())
+}
+
+/// Check that `actor` may ban, suspend or mute `target` until the given time
+pub fn check_sanction(
+    actor: (UserId, Role),
+    target: (UserId, Role),
+    until: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> Result<(), AdminError> {
+    if actor.0 == target.0 {
+        return Err(AdminError::CannotSanctionSelf);
+    }
+    if target.1.has(actor.1) {
+        return Err(AdminError::CannotSanctionPrivileged);
+    }
+    if until.map(|until| until <= now).unwrap_or(false) {
+        return Err(AdminError::InvalidSanctionPeriod);
+    }
+    Ok(())
+}
+
+/// Check that a ban of `network` doesn't lock out the admin banning it
+pub fn check_ip_ban(network: &IpNetwork, own_ip: IpAddr) -> Result<(), AdminError> {
+    if network.contains(own_ip) {
+        Err(AdminError::CannotBanOwnIp)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the number of items of a bulk request
+pub fn check_bulk_items(len: usize) -> Result<(), AdminError> {
+    if len > MAX_BULK_ITEMS {
+        Err(AdminError::TooManyItems {
+            max: MAX_BULK_ITEMS as u32,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the time range of a bulk or stats request
+pub fn check_time_range(
+    since: Option<NaiveDateTime>,
+    until: Option<NaiveDateTime>,
+) -> Result<(), AdminError> {
+    match (since, until) {
+        (Some(since), Some(until)) if since >= until => Err(AdminError::InvalidTimeRange),
+        _ => Ok(()),
+    }
+}
+
+/// Check the time range of a stats request, which may cover at most
+/// `MAX_STATS_BUCKETS` buckets
+pub fn check_stats_range(
+    bucket: StatsBucket,
+    since: NaiveDateTime,
+    until: NaiveDateTime,
+) -> Result<(), AdminError> {
+    check_time_range(Some(since), Some(until))?;
+    let max = bucket.duration() * MAX_STATS_BUCKETS as i32;
+    if until - bucket.start(since) > max {
+        return Err(AdminError::TooManyItems {
+            max: MAX_STATS_BUCKETS as u32,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use chrono::Duration;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn keeps_one_admin() {
+        let (a, b) = (UserId::from(1), UserId::from(2));
+        assert_eq!(
+            check_role_change(a, a, Role::Admin, Role::User, 2),
+            Err(AdminError::CannotChangeOwnRole)
+        );
+        assert_eq!(
+            check_role_change(a, b, Role::Admin, Role::Moderator, 1),
+            Err(AdminError::LastAdmin)
+        );
+        assert_eq!(check_role_change(a, b, Role::Admin, Role::User, 2), Ok(()));
+        assert_eq!(check_role_change(a, b, Role::User, Role::Admin, 1), Ok(()));
+    }
+
+    #[test]
+    fn protects_privileged_users() {
+        let now = NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0);
+        let admin = (UserId::from(1), Role::Admin);
+        let moderator = (UserId::from(2), Role::Moderator);
+        let user = (UserId::from(3), Role::User);
+        assert_eq!(
+            check_sanction(moderator, admin, None, now),
+            Err(AdminError::CannotSanctionPrivileged)
+        );
+        assert_eq!(
+            check_sanction(moderator, moderator, None, now),
+            Err(AdminError::CannotSanctionSelf)
+        );
+        assert_eq!(
+            check_sanction(moderator, user, Some(now), now),
+            Err(AdminError::InvalidSanctionPeriod)
+        );
+        assert_eq!(check_sanction(admin, moderator, None, now), Ok(()));
+    }
+
+    #[test]
+    fn refuses_to_ban_own_ip() {
+        let network = IpNetwork::try_from("10.0.0.0/8").unwrap();
+        assert_eq!(
+            check_ip_ban(&network, "10.1.2.3".parse().unwrap()),
+            Err(AdminError::CannotBanOwnIp)
+        );
+        assert_eq!(check_ip_ban(&network, "192.168.0.1".parse().unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn limits_bulk_items() {
+        assert_eq!(check_bulk_items(0), Ok(()));
+        assert_eq!(check_bulk_items(MAX_BULK_ITEMS), Ok(()));
+        assert_eq!(
+            check_bulk_items(MAX_BULK_ITEMS + 1),
+            Err(AdminError::TooManyItems {
+                max: MAX_BULK_ITEMS as u32
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_inverted_time_ranges() {
+        let since = NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0);
+        let until = NaiveDate::from_ymd(2018, 10, 2).and_hms(12, 0, 0);
+        assert_eq!(check_time_range(Some(since), Some(until)), Ok(()));
+        assert_eq!(check_time_range(None, Some(until)), Ok(()));
+        assert_eq!(check_time_range(Some(since), None), Ok(()));
+        assert_eq!(
+            check_time_range(Some(since), Some(since)),
+            Err(AdminError::InvalidTimeRange)
+        );
+        assert_eq!(
+            check_time_range(Some(until), Some(since)),
+            Err(AdminError::InvalidTimeRange)
+        );
+        assert_eq!(
+            check_stats_range(StatsBucket::Day, until, since),
+            Err(AdminError::InvalidTimeRange)
+        );
+    }
+
+    #[test]
+    fn limits_stats_buckets() {
+        let too_many = Err(AdminError::TooManyItems {
+            max: MAX_STATS_BUCKETS as u32,
+        });
+        // 2018-10-01 was a monday, so it is the start of both a day and a week
+        let since = NaiveDate::from_ymd(2018, 10, 1).and_hms(0, 0, 0);
+        for bucket in [StatsBucket::Day, StatsBucket::Week].iter() {
+            let max = bucket.duration() * MAX_STATS_BUCKETS as i32;
+            assert_eq!(check_stats_range(*bucket, since, since + max), Ok(()));
+            assert_eq!(
+                check_stats_range(*bucket, since, since + max + Duration::seconds(1)),
+                too_many
+            );
+            assert_eq!(bucket.series(since, since + max).len(), MAX_STATS_BUCKETS);
+        }
+        // The range is counted from the start of the first bucket
+        let since = since + Duration::hours(12);
+        let max = StatsBucket::Day.duration() * MAX_STATS_BUCKETS as i32;
+        assert_eq!(check_stats_range(StatsBucket::Day, since, since + max), too_many);
+    }
+}
@@ -0,0 +1,207 @@
+//! Time-bucketed activity statistics for the admin dashboard
+
+use chrono::naive::NaiveDateTime;
+use chrono::{Datelike, Duration};
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+/// The maximum number of buckets a single stats request may cover
+pub const MAX_STATS_BUCKETS: usize = 366;
+
+/// The size of the time buckets the counts are grouped in
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatsBucket {
+    Day,
+    /// A week starting on monday
+    Week,
+}
+
+impl StatsBucket {
+    /// The length of a bucket
+    pub fn duration(self) -> Duration {
+        match self {
+            StatsBucket::Day => Duration::days(1),
+            StatsBucket::Week => Duration::weeks(1),
+        }
+    }
+
+    /// The start of the bucket which contains the given time
+    pub fn start(self, time: NaiveDateTime) -> NaiveDateTime {
+        let day = time.date().and_hms(0, 0, 0);
+        match self {
+            StatsBucket::Day => day,
+            StatsBucket::Week => {
+                day - Duration::days(i64::from(day.weekday().num_days_from_monday()))
+            }
+        }
+    }
+
+    /// The start of every bucket which overlaps `since..until`, oldest first
+    ///
+    /// At most `MAX_STATS_BUCKETS` are returned.
+    pub fn series(self, since: NaiveDateTime, until: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut starts = Vec::new();
+        let mut start = self.start(since);
+        while start < until && starts.len() < MAX_STATS_BUCKETS {
+            starts.push(start);
+            start += self.duration();
+        }
+        starts
+    }
+}
+
+/// The activity during a single bucket
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct StatsPointPayload {
+    pub start: NaiveDateTime,
+    pub registrations: u32,
+    pub threads: u32,
+    pub comments: u32,
+    pub reports: u32,
+    pub logins: u32,
+    pub login_failures: u32,
+}
+
+impl StatsPointPayload {
+    /// A bucket without any activity
+    pub fn empty(start: NaiveDateTime) -> Self {
+        StatsPointPayload {
+            start,
+            registrations: 0,
+            threads: 0,
+            comments: 0,
+            reports: 0,
+            logins: 0,
+            login_failures: 0,
+        }
+    }
+
+    /// Add the activity of `other` to this point, keeping the start of this
+    /// point
+    fn add(self, other: &StatsPointPayload) -> Self {
+        StatsPointPayload {
+            start: self.start,
+            registrations: self.registrations.saturating_add(other.registrations),
+            threads: self.threads.saturating_add(other.threads),
+            comments: self.comments.saturating_add(other.comments),
+            reports: self.reports.saturating_add(other.reports),
+            logins: self.logins.saturating_add(other.logins),
+            login_failures: self.login_failures.saturating_add(other.login_failures),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct CategoryStatsPayload {
+    pub id: CategoryId,
+    pub title: Title,
+    pub threads: u32,
+    pub comments: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct UserActivityPayload {
+    pub id: UserId,
+    pub username: Username,
+    pub threads: u32,
+    pub comments: u32,
+}
+
+/// Activity statistics, where `series` has one point per bucket (including
+/// empty buckets) so it can be charted directly
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct StatsPayload {
+    pub bucket: StatsBucket,
+    pub series: Vec<StatsPointPayload>,
+    /// The most active categories, most active first
+    pub top_categories: Vec<CategoryStatsPayload>,
+    /// The most active users, most active first
+    pub top_users: Vec<UserActivityPayload>,
+}
+
+impl StatsPayload {
+    /// Create the statistics from the buckets which had activity, filling in
+    /// empty points for the buckets in `since..until` without activity
+    ///
+    /// Points which fall into the same bucket are summed.
+    pub fn new(
+        bucket: StatsBucket,
+        since: NaiveDateTime,
+        until: NaiveDateTime,
+        points: Vec<StatsPointPayload>,
+    ) -> Self {
+        let series = bucket
+            .series(since, until)
+            .into_iter()
+            .map(|start| {
+                points
+                    .iter()
+                    .filter(|p| bucket.start(p.start) == start)
+                    .fold(StatsPointPayload::empty(start), |sum, p| sum.add(p))
+            })
+            .collect();
+        StatsPayload {
+            bucket,
+            series,
+            top_categories: Vec::new(),
+            top_users: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+
+    fn time(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(2018, 10, day).and_hms(hour, 0, 0)
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 2018-10-01 was a monday
+        assert_eq!(StatsBucket::Week.start(time(4, 12)), time(1, 0));
+        assert_eq!(StatsBucket::Day.start(time(4, 12)), time(4, 0));
+    }
+
+    #[test]
+    fn fills_empty_buckets() {
+        let point = StatsPointPayload {
+            comments: 3,
+            ..StatsPointPayload::empty(time(2, 15))
+        };
+        let stats = StatsPayload::new(StatsBucket::Day, time(1, 10), time(3, 10), vec![point]);
+        let comments: Vec<u32> = stats.series.iter().map(|p| p.comments).collect();
+        assert_eq!(comments, vec![0, 3, 0]);
+        assert_eq!(stats.series[1].start, time(2, 0));
+    }
+
+    #[test]
+    fn sums_points_in_the_same_bucket() {
+        let points = vec![
+            StatsPointPayload {
+                comments: 3,
+                logins: 1,
+                ..StatsPointPayload::empty(time(2, 0))
+            },
+            StatsPointPayload {
+                comments: 2,
+                registrations: 1,
+                ..StatsPointPayload::empty(time(4, 15))
+            },
+        ];
+        let stats = StatsPayload::new(StatsBucket::Week, time(1, 0), time(7, 0), points);
+        assert_eq!(stats.series.len(), 1);
+        assert_eq!(
+            stats.series[0],
+            StatsPointPayload {
+                registrations: 1,
+                comments: 5,
+                logins: 1,
+                ..StatsPointPayload::empty(time(1, 0))
+            }
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Attachment requests from and responses to the web-client
+
+pub mod policy;
+pub mod requests;
+pub mod responses;
+pub mod sniff;
@@ -0,0 +1,157 @@
+//! Limits on the files which can be uploaded as attachments
+
+use crate::attachments::responses::AttachmentError;
+use crate::attachments::sniff::{image_dimensions, ContentType, Dimensions};
+
+/// The limits which an uploaded file must be within to be accepted
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AttachmentPolicy {
+    /// The maximum size of a file in bytes
+    pub max_size: u64,
+    /// The maximum width of an image in pixels
+    pub max_width: u32,
+    /// The maximum height of an image in pixels
+    pub max_height: u32,
+    /// The maximum number of attachments on a single thread or comment
+    pub max_per_post: usize,
+    /// The content types which are accepted
+    pub allowed_types: Vec<ContentType>,
+}
+
+impl Default for AttachmentPolicy {
+    fn default() -> Self {
+        AttachmentPolicy {
+            max_size: 5 * 1024 * 1024,
+            max_width: 4096,
+            max_height: 4096,
+            max_per_post: 4,
+            allowed_types: vec![
+                ContentType::Png,
+                ContentType::Jpeg,
+                ContentType::Gif,
+                ContentType::Webp,
+            ],
+        }
+    }
+}
+
+/// The properties of a file which has been accepted by a policy
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub struct AttachmentInfo {
+    pub content_type: ContentType,
+    /// The size of the file in bytes
+    pub size: u64,
+    pub dimensions: Option<Dimensions>,
+}
+
+impl AttachmentPolicy {
+    /// Vertify that the contents of a file is within the limits of the policy
+    ///
+    /// The content type is sniffed from the data, so the type declared by
+    /// the client is not taken into account.
+    pub fn check(&self, data: &[u8]) -> Result<AttachmentInfo, AttachmentError> {
+        let size = data.len() as u64;
+        if size > self.max_size {
+            return Err(AttachmentError::TooLarge);
+        }
+        let content_type = ContentType::sniff(data)
+            .filter(|t| self.allowed_types.contains(t))
+            .ok_or(AttachmentError::UnsupportedType)?;
+        let dimensions = if content_type.is_image() {
+            let dimensions =
+                image_dimensions(data, content_type).ok_or(AttachmentError::MalformedFile)?;
+            if dimensions.width > self.max_width || dimensions.height > self.max_height {
+                return Err(AttachmentError::DimensionsTooLarge);
+            }
+            Some(dimensions)
+        } else {
+            None
+        };
+        Ok(AttachmentInfo {
+            content_type,
+            size,
+            dimensions,
+        })
+    }
+
+    /// Vertify that the number of attachments on a post is within the policy
+    pub fn check_count(&self, count: usize) -> Result<(), AttachmentError> {
+        if count <= self.max_per_post {
+            Ok(())
+        } else {
+            Err(AttachmentError::TooManyAttachments)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u8, height: u8) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0, width, 0, 0, 0, height]);
+        png
+    }
+
+    fn policy() -> AttachmentPolicy {
+        AttachmentPolicy {
+            max_size: 24,
+            max_width: 100,
+            max_height: 50,
+            max_per_post: 2,
+            allowed_types: vec![ContentType::Png, ContentType::Pdf],
+        }
+    }
+
+    #[test]
+    fn accepts_files_within_limits() {
+        let data = png(100, 50);
+        assert_eq!(
+            policy().check(&data),
+            Ok(AttachmentInfo {
+                content_type: ContentType::Png,
+                size: 24,
+                dimensions: Some(Dimensions {
+                    width: 100,
+                    height: 50,
+                }),
+            })
+        );
+        let info = policy().check(b"%PDF-1.4").unwrap();
+        assert_eq!(info.content_type, ContentType::Pdf);
+        assert_eq!(info.dimensions, None);
+    }
+
+    #[test]
+    fn rejects_files_outside_limits() {
+        let mut data = png(10, 10);
+        data.push(0);
+        assert_eq!(policy().check(&data), Err(AttachmentError::TooLarge));
+        assert_eq!(
+            policy().check(&png(101, 50)),
+            Err(AttachmentError::DimensionsTooLarge)
+        );
+        assert_eq!(
+            policy().check(&png(100, 51)),
+            Err(AttachmentError::DimensionsTooLarge)
+        );
+        assert_eq!(
+            policy().check(b"GIF89a\x40\x01\xf0\x00"),
+            Err(AttachmentError::UnsupportedType)
+        );
+        assert_eq!(
+            policy().check(b"\x89PNG\r\n\x1a\n"),
+            Err(AttachmentError::MalformedFile)
+        );
+    }
+
+    #[test]
+    fn limits_attachments_per_post() {
+        assert_eq!(policy().check_count(2), Ok(()));
+        assert_eq!(
+            policy().check_count(3),
+            Err(AttachmentError::TooManyAttachments)
+        );
+    }
+}
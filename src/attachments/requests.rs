@@ -0,0 +1,56 @@
+//! The requests a user can make to the attachment-service
+
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum AttachmentRequest {
+    UploadAttachment(UploadAttachmentPayload),
+    DeleteAttachment(DeleteAttachmentPayload),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetAttachmentPayload {
+    pub id: AttachmentId,
+}
+
+/// The metadata of an upload, which is sent next to the file itself
+///
+/// In a multipart form this is one part while the file is another part, the
+/// two are combined with `UploadAttachmentPayload::new`.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct UploadMetadataPayload {
+    pub filename: FileName,
+    /// The content type claimed by the client, this is only informational as
+    /// the actual type is sniffed from the data
+    pub declared_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct UploadAttachmentPayload {
+    pub user_id: Option<UserId>,
+    #[serde(flatten)]
+    pub metadata: UploadMetadataPayload,
+    pub data: Vec<u8>,
+}
+
+impl UploadAttachmentPayload {
+    pub fn new(metadata: UploadMetadataPayload, data: impl Into<Vec<u8>>) -> Self {
+        UploadAttachmentPayload {
+            user_id: None,
+            metadata,
+            data: data.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct DeleteAttachmentPayload {
+    pub id: AttachmentId,
+    pub user_id: Option<UserId>,
+}
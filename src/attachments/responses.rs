@@ -0,0 +1,66 @@
+//! The responses a user will get on requests to the attachment-service
+
+use chrono::naive::NaiveDateTime;
+use crate::attachments::sniff::{ContentType, Dimensions};
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+/// All the successful responses to a `AttachmentRequest`
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum AttachmentSuccess {
+    Attachment(AttachmentPayload),
+    AttachmentDeleted,
+}
+
+/// All the unsuccessful responses to a `AttachmentRequest`
+#[derive(Fail, Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub enum AttachmentError {
+    #[fail(display = "attachment was not found")]
+    MissingAttachment,
+    #[fail(display = "file is too large")]
+    TooLarge,
+    #[fail(display = "image dimensions are too large")]
+    DimensionsTooLarge,
+    #[fail(display = "file type is not supported")]
+    UnsupportedType,
+    #[fail(display = "file is malformed")]
+    MalformedFile,
+    #[fail(display = "too many attachments")]
+    TooManyAttachments,
+    #[fail(display = "internal server error")]
+    InternalServerError,
+}
+
+/// A reference to an uploaded attachment from a thread or a comment
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct AttachmentRef {
+    pub id: AttachmentId,
+}
+
+impl From<AttachmentId> for AttachmentRef {
+    fn from(id: AttachmentId) -> Self {
+        AttachmentRef { id }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AttachmentPayload {
+    pub id: AttachmentId,
+    pub user_id: UserId,
+    pub filename: FileName,
+    pub content_type: ContentType,
+    pub size: u64,
+    pub dimensions: Option<Dimensions>,
+    pub timestamp: NaiveDateTime,
+}
+
+impl<'a> From<&'a AttachmentPayload> for AttachmentRef {
+    fn from(attachment: &'a AttachmentPayload) -> Self {
+        AttachmentRef { id: attachment.id }
+    }
+}
@@ -0,0 +1,215 @@
+//! Detection of the content type and dimensions of uploaded files
+//!
+//! The content type declared by a client is never trusted, instead it is
+//! sniffed from the magic bytes at the start of the file.
+
+/// The content types which can be detected from the contents of a file
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ContentType {
+    #[serde(rename = "image/png")]
+    Png,
+    #[serde(rename = "image/jpeg")]
+    Jpeg,
+    #[serde(rename = "image/gif")]
+    Gif,
+    #[serde(rename = "image/webp")]
+    Webp,
+    #[serde(rename = "application/pdf")]
+    Pdf,
+}
+
+impl ContentType {
+    /// Detect the content type of a file from its magic bytes
+    pub fn sniff(data: &[u8]) -> Option<ContentType> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ContentType::Png)
+        } else if data.starts_with(b"\xff\xd8\xff") {
+            Some(ContentType::Jpeg)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(ContentType::Gif)
+        } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(ContentType::Webp)
+        } else if data.starts_with(b"%PDF-") {
+            Some(ContentType::Pdf)
+        } else {
+            None
+        }
+    }
+
+    /// The MIME type of the content type
+    pub fn mime(self) -> &'static str {
+        match self {
+            ContentType::Png => "image/png",
+            ContentType::Jpeg => "image/jpeg",
+            ContentType::Gif => "image/gif",
+            ContentType::Webp => "image/webp",
+            ContentType::Pdf => "application/pdf",
+        }
+    }
+
+    /// Check if the content type is an image which has dimensions
+    pub fn is_image(self) -> bool {
+        self != ContentType::Pdf
+    }
+}
+
+/// The width and height of an image in pixels
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Dimensions {
+    fn new(width: u32, height: u32) -> Self {
+        Dimensions { width, height }
+    }
+}
+
+fn u16_be(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 2)
+        .map(|b| u32::from(b[0]) << 8 | u32::from(b[1]))
+}
+
+fn u16_le(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 2)
+        .map(|b| u32::from(b[1]) << 8 | u32::from(b[0]))
+}
+
+fn u24_le(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 3)
+        .map(|b| u32::from(b[2]) << 16 | u32::from(b[1]) << 8 | u32::from(b[0]))
+}
+
+fn u32_be(data: &[u8], at: usize) -> Option<u32> {
+    Some(u16_be(data, at)? << 16 | u16_be(data, at + 2)?)
+}
+
+/// Read the dimensions of an image of the given content type
+///
+/// Returns `None` if the content type is not an image or if the image header
+/// is malformed.
+pub fn image_dimensions(data: &[u8], content_type: ContentType) -> Option<Dimensions> {
+    match content_type {
+        ContentType::Png => {
+            if data.get(12..16) != Some(&b"IHDR"[..]) {
+                return None;
+            }
+            Some(Dimensions::new(u32_be(data, 16)?, u32_be(data, 20)?))
+        }
+        ContentType::Gif => Some(Dimensions::new(u16_le(data, 6)?, u16_le(data, 8)?)),
+        ContentType::Jpeg => jpeg_dimensions(data),
+        ContentType::Webp => webp_dimensions(data),
+        ContentType::Pdf => None,
+    }
+}
+
+/// Find the dimensions in the first start-of-frame segment of a JPEG
+fn jpeg_dimensions(data: &[u8]) -> Option<Dimensions> {
+    let mut pos = 2;
+    loop {
+        if *data.get(pos)? != 0xff {
+            return None;
+        }
+        // Markers can be padded with any number of fill bytes
+        while *data.get(pos + 1)? == 0xff {
+            pos += 1;
+        }
+        let marker = *data.get(pos + 1)?;
+        match marker {
+            0xd0..=0xd7 | 0x01 => pos += 2,
+            0xc0..=0xcf if marker != 0xc4 && marker != 0xc8 && marker != 0xcc => {
+                let height = u16_be(data, pos + 5)?;
+                let width = u16_be(data, pos + 7)?;
+                return Some(Dimensions::new(width, height));
+            }
+            _ => pos += 2 + u16_be(data, pos + 2)? as usize,
+        }
+    }
+}
+
+/// Find the dimensions in the first chunk of a WebP image
+fn webp_dimensions(data: &[u8]) -> Option<Dimensions> {
+    match data.get(12..16)? {
+        b"VP8 " => Some(Dimensions::new(
+            u16_le(data, 26)? & 0x3fff,
+            u16_le(data, 28)? & 0x3fff,
+        )),
+        b"VP8L" => {
+            let b = data.get(21..25)?;
+            let (b0, b1, b2, b3) = (
+                u32::from(b[0]),
+                u32::from(b[1]),
+                u32::from(b[2]),
+                u32::from(b[3]),
+            );
+            Some(Dimensions::new(
+                1 + ((b1 & 0x3f) << 8 | b0),
+                1 + ((b3 & 0x0f) << 10 | b2 << 2 | (b1 & 0xc0) >> 6),
+            ))
+        }
+        b"VP8X" => Some(Dimensions::new(1 + u24_le(data, 24)?, 1 + u24_le(data, 27)?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff_dimensions(data: &[u8]) -> Option<(ContentType, Dimensions)> {
+        let content_type = ContentType::sniff(data)?;
+        Some((content_type, image_dimensions(data, content_type)?))
+    }
+
+    #[test]
+    fn sniffs_png() {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 1, 0, 0, 0, 0, 200]);
+        assert_eq!(
+            sniff_dimensions(&png),
+            Some((ContentType::Png, Dimensions::new(256, 200)))
+        );
+    }
+
+    #[test]
+    fn sniffs_gif() {
+        let gif = b"GIF89a\x40\x01\xf0\x00";
+        assert_eq!(
+            sniff_dimensions(gif),
+            Some((ContentType::Gif, Dimensions::new(320, 240)))
+        );
+    }
+
+    #[test]
+    fn sniffs_jpeg() {
+        let mut jpeg = b"\xff\xd8".to_vec();
+        // An APP0 segment which is skipped
+        jpeg.extend_from_slice(b"\xff\xe0\x00\x04\x00\x00");
+        // A baseline start-of-frame segment
+        jpeg.extend_from_slice(b"\xff\xc0\x00\x11\x08\x01\xe0\x02\x80");
+        assert_eq!(
+            sniff_dimensions(&jpeg),
+            Some((ContentType::Jpeg, Dimensions::new(640, 480)))
+        );
+    }
+
+    #[test]
+    fn sniffs_webp() {
+        let mut webp = b"RIFF\x00\x00\x00\x00WEBPVP8X".to_vec();
+        webp.extend_from_slice(&[0; 8]);
+        webp.extend_from_slice(&[0x7f, 0x02, 0x00, 0xdf, 0x01, 0x00]);
+        assert_eq!(
+            sniff_dimensions(&webp),
+            Some((ContentType::Webp, Dimensions::new(640, 480)))
+        );
+    }
+
+    #[test]
+    fn ignores_declared_type() {
+        assert_eq!(ContentType::sniff(b"%PDF-1.4"), Some(ContentType::Pdf));
+        assert_eq!(ContentType::sniff(b"<html><body>"), None);
+        assert_eq!(ContentType::sniff(b""), None);
+        assert_eq!(image_dimensions(b"\x89PNG\r\n\x1a\n", ContentType::Png), None);
+    }
+}
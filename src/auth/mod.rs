@@ -1,4 +1,5 @@
 //! Authentication requests to and responses from the web-client
 
+pub mod registration;
 pub mod requests;
 pub mod responses;
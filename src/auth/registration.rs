@@ -0,0 +1,188 @@
+//! Registration modes, which control who is allowed to sign up
+
+use chrono::naive::NaiveDateTime;
+use crate::auth::responses::AuthError;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+/// How new users are allowed to register
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(
+    tag = "type",
+    content = "domains",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum RegistrationMode {
+    /// Anyone can register
+    Open,
+    /// Only users with a valid invite code can register
+    InviteOnly,
+    /// Anyone can register, but an admin has to approve the account before
+    /// it can be used
+    Approval,
+    /// Only users with an email address in one of the domains (or their
+    /// subdomains) can register
+    DomainAllowList(Vec<String>),
+    /// Nobody can register
+    Closed,
+}
+
+impl Default for RegistrationMode {
+    fn default() -> Self {
+        RegistrationMode::Open
+    }
+}
+
+/// The state of an account right after registration
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RegistrationState {
+    Active,
+    PendingApproval,
+}
+
+impl RegistrationMode {
+    /// Check if a user with the given email and invite code may register
+    ///
+    /// The invite code is only checked for presence, the service has to
+    /// check that it exists and is usable.
+    pub fn check(
+        &self,
+        email: &Email,
+        invite_code: Option<&InviteCode>,
+    ) -> Result<RegistrationState, AuthError> {
+        match self {
+            RegistrationMode::Open => Ok(RegistrationState::Active),
+            RegistrationMode::InviteOnly => invite_code
+                .map(|_| RegistrationState::Active)
+                .ok_or(AuthError::InviteRequired),
+            RegistrationMode::Approval => Ok(RegistrationState::PendingApproval),
+            RegistrationMode::DomainAllowList(domains) => {
+                let domain = email.ascii_domain();
+                if domains.iter().any(|d| domain_matches(&domain, d)) {
+                    Ok(RegistrationState::Active)
+                } else {
+                    Err(AuthError::EmailDomainNotAllowed)
+                }
+            }
+            RegistrationMode::Closed => Err(AuthError::RegistrationClosed),
+        }
+    }
+}
+
+/// Check if the (lowercase ASCII) `domain` is `allowed` or one of its
+/// subdomains
+fn domain_matches(domain: &str, allowed: &str) -> bool {
+    let allowed = allowed.trim_start_matches('.');
+    let allowed = idna::domain_to_ascii(allowed).unwrap_or_else(|_| allowed.to_lowercase());
+    domain == allowed || domain.ends_with(&format!(".{}", allowed))
+}
+
+/// An invite which lets new users register when the forum is invite only
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct InvitePayload {
+    pub code: InviteCode,
+    pub created_by: UserId,
+    pub created_at: NaiveDateTime,
+    pub expires: Option<NaiveDateTime>,
+    /// The number of registrations the invite can be used for, `None` if it
+    /// is unlimited
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub revoked: bool,
+}
+
+impl InvitePayload {
+    /// Check if the invite can be used to register at the given time
+    pub fn is_usable(&self, now: NaiveDateTime) -> bool {
+        !self.revoked
+            && self.expires.map(|e| now < e).unwrap_or(true)
+            && self.max_uses.map(|m| self.uses < m).unwrap_or(true)
+    }
+}
+
+/// An account which is waiting for approval by an admin
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct PendingUserPayload {
+    pub id: UserId,
+    pub username: Username,
+    pub email: Email,
+    pub registered: NaiveDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use std::convert::TryFrom;
+
+    fn email(s: &str) -> Email {
+        Email::try_from(s.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn allows_listed_domains() {
+        let mode = RegistrationMode::DomainAllowList(vec!["ntnu.no".into()]);
+        assert_eq!(
+            mode.check(&email("john@ntnu.no"), None),
+            Ok(RegistrationState::Active)
+        );
+        assert_eq!(
+            mode.check(&email("john@stud.NTNU.no"), None),
+            Ok(RegistrationState::Active)
+        );
+        assert_eq!(
+            mode.check(&email("john@notntnu.no"), None),
+            Err(AuthError::EmailDomainNotAllowed)
+        );
+    }
+
+    #[test]
+    fn requires_invite_code() {
+        let code = InviteCode::try_from("Xk3_9fQ-".to_owned()).unwrap();
+        let mode = RegistrationMode::InviteOnly;
+        assert_eq!(
+            mode.check(&email("john@example.com"), None),
+            Err(AuthError::InviteRequired)
+        );
+        assert_eq!(
+            mode.check(&email("john@example.com"), Some(&code)),
+            Ok(RegistrationState::Active)
+        );
+    }
+
+    #[test]
+    fn invites_become_unusable() {
+        let time = |hour| NaiveDate::from_ymd(2018, 10, 1).and_hms(hour, 0, 0);
+        let invite = InvitePayload {
+            code: InviteCode::try_from("Xk3_9fQ-".to_owned()).unwrap(),
+            created_by: UserId::from(1),
+            created_at: time(10),
+            expires: Some(time(12)),
+            max_uses: Some(2),
+            uses: 1,
+            revoked: false,
+        };
+        assert!(invite.is_usable(time(11)));
+        assert!(!invite.is_usable(time(12)));
+
+        let used_up = InvitePayload { uses: 2, ..invite };
+        assert!(!used_up.is_usable(time(11)));
+
+        let revoked = InvitePayload {
+            uses: 0,
+            revoked: true,
+            ..used_up
+        };
+        assert!(!revoked.is_usable(time(11)));
+
+        let unlimited = InvitePayload {
+            expires: None,
+            max_uses: None,
+            uses: 1000,
+            revoked: false,
+            ..revoked
+        };
+        assert!(unlimited.is_usable(time(23)));
+    }
+}
@@ -15,6 +15,9 @@ pub enum AuthRequest {
     Authenticate(AuthPayload),
     Deauthenticate(EmptyPayload),
     RegisterUser(RegisterUserPayload),
+    RequestAccountDeletion(AccountDeletionPayload),
+    CancelAccountDeletion(EmptyPayload),
+    ExportMyData(EmptyPayload),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,9 +28,19 @@ pub struct AuthPayload {
 
 #[derive(Serialize, Deserialize)]
 pub struct RegisterUserPayload {
+    #[serde(deserialize_with = "deserialize_new_username")]
     pub username: Username,
     pub password: PlainPassword,
     pub email: Email,
+    /// Required when the forum is invite only
+    pub invite_code: Option<InviteCode>,
+}
+
+/// Schedule the deletion of the account of the authenticated user, the
+/// password is required to confirm the request
+#[derive(Serialize, Deserialize)]
+pub struct AccountDeletionPayload {
+    pub password: PlainPassword,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,5 +1,13 @@
 //! The responses a user will get from requests to the auth-service
 
+use chrono::naive::NaiveDateTime;
+use chrono::Duration;
+use crate::export::DataExport;
+
+/// The number of days from a deletion is requested until the account is
+/// deleted, during which the deletion can be cancelled
+pub const ACCOUNT_DELETION_GRACE_DAYS: i64 = 30;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(
     tag = "type",
@@ -10,6 +18,26 @@ pub enum AuthSuccess {
     Authenticated,
     Deauthenticated,
     UserRegistered,
+    /// The user is registered, but cannot log in until approved by an admin
+    RegistrationPending,
+    DeletionScheduled(DeletionScheduledPayload),
+    DeletionCancelled,
+    DataExported(Box<DataExport>),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct DeletionScheduledPayload {
+    /// The time the account will be deleted unless the deletion is cancelled
+    pub scheduled_for: NaiveDateTime,
+}
+
+impl DeletionScheduledPayload {
+    /// Schedule a deletion requested at the given time
+    pub fn new(requested: NaiveDateTime) -> Self {
+        DeletionScheduledPayload {
+            scheduled_for: requested + Duration::days(ACCOUNT_DELETION_GRACE_DAYS),
+        }
+    }
 }
 
 #[derive(Fail, Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
@@ -24,6 +52,26 @@ pub enum AuthError {
     InvalidCredentials,
     #[fail(display = "user already exists")]
     ExistingUser,
+    #[fail(display = "account deletion is already scheduled")]
+    DeletionAlreadyScheduled,
+    #[fail(display = "no account deletion is scheduled")]
+    NoDeletionScheduled,
+    #[fail(display = "user is banned")]
+    Banned,
+    #[fail(display = "user is suspended until {}", until)]
+    Suspended { until: NaiveDateTime },
+    #[fail(display = "registration is closed")]
+    RegistrationClosed,
+    #[fail(display = "an invite code is required to register")]
+    InviteRequired,
+    #[fail(display = "invite code is invalid, expired or used up")]
+    InvalidInviteCode,
+    #[fail(display = "email domain is not allowed to register")]
+    EmailDomainNotAllowed,
+    #[fail(display = "account is awaiting approval by an admin")]
+    PendingApproval,
+    #[fail(display = "account registration was rejected")]
+    RegistrationRejected,
     #[fail(display = "internal server error")]
     InternalServerError,
 }
@@ -35,6 +83,19 @@ pub enum Role {
     User = 10,
 }
 
+impl Role {
+    /// Check if this role is at least as privileged as `required`
+    pub fn has(self, required: Role) -> bool {
+        self as u32 >= required as u32
+    }
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::User
+    }
+}
+
 impl<'a> From<&'a str> for Role {
     fn from(s: &'a str) -> Self {
         match s {
@@ -74,4 +135,19 @@ impl serde::Serialize for Role {
         let string: String = Role::into(*self);
         serializer.serialize_str(&string)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+
+    #[test]
+    fn deletion_is_scheduled_after_grace_period() {
+        let requested = NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            DeletionScheduledPayload::new(requested).scheduled_for,
+            NaiveDate::from_ymd(2018, 10, 31).and_hms(12, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Filters which run over user generated content before it is published
+//!
+//! A filter is configured with a list of banned words and regexes, each with
+//! an action (reject, mask or hold for review), and a couple of heuristics
+//! which catch common spam. Custom classifiers can be plugged in through the
+//! `Classifier` trait.
+
+use crate::content::responses::ContentError;
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+/// The action taken when a rule matches
+///
+/// The actions are ordered by severity, so the most severe action of all the
+/// matching rules wins.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FilterAction {
+    /// Replace the matching text with `*`
+    Mask,
+    /// Accept the content, but hide it until a moderator has reviewed it
+    Hold,
+    /// Refuse to accept the content
+    Reject,
+}
+
+/// What a rule matches
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(
+    tag = "type",
+    content = "pattern",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum FilterPattern {
+    /// A whole word, matched case-insensitively
+    Word(String),
+    Regex(String),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct FilterRule {
+    pub pattern: FilterPattern,
+    pub action: FilterAction,
+}
+
+/// The configuration of a content filter, as stored by the admins
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct FilterConfig {
+    pub rules: Vec<FilterRule>,
+    /// Hold content with more links than this for review
+    pub max_links: Option<usize>,
+    /// Hold content which repeats a character more times in a row than this
+    /// for review
+    pub max_repeated_chars: Option<usize>,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        FilterConfig {
+            rules: Vec::new(),
+            max_links: Some(5),
+            max_repeated_chars: Some(10),
+        }
+    }
+}
+
+/// Why content was rejected or held for review
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FilterReason {
+    BannedWord,
+    TooManyLinks,
+    RepeatedCharacters,
+    /// Flagged by a custom classifier
+    Classified,
+}
+
+/// The result of running content through a filter
+#[derive(PartialEq, Clone, Debug)]
+pub enum Verdict {
+    Allow,
+    /// Accept the content with the given text, where banned words are masked
+    Mask(String),
+    Hold(FilterReason),
+    Reject(FilterReason),
+}
+
+impl Verdict {
+    fn severity(&self) -> u8 {
+        match self {
+            Verdict::Allow => 0,
+            Verdict::Mask(_) => 1,
+            Verdict::Hold(_) => 2,
+            Verdict::Reject(_) => 3,
+        }
+    }
+
+    /// Combine two verdicts, keeping the most severe
+    pub fn merge(self, other: Verdict) -> Verdict {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The error the user should get, if the content isn't published right
+    /// away
+    pub fn error(&self) -> Option<ContentError> {
+        match self {
+            Verdict::Allow | Verdict::Mask(_) => None,
+            Verdict::Hold(reason) => Some(ContentError::HeldForReview { reason: *reason }),
+            Verdict::Reject(reason) => Some(ContentError::Rejected { reason: *reason }),
+        }
+    }
+}
+
+/// A custom classifier, e.g. a bayesian spam filter or an external service
+///
+/// The classifier gets the text as it was typed, with banned words already
+/// masked, and a `Verdict::Mask` it returns should contain such text as well.
+pub trait Classifier {
+    fn classify(&self, text: &str) -> Verdict;
+}
+
+/// A rule in the configuration is not a valid regex
+#[derive(Fail, PartialEq, Clone, Copy, Debug)]
+#[fail(display = "filter rule {} is not a valid pattern", index)]
+pub struct InvalidFilterRule {
+    pub index: usize,
+}
+
+/// A compiled rule
+struct Rule {
+    re: Regex,
+    action: FilterAction,
+    /// Only match whole words, i.e. not directly next to other letters
+    whole_word: bool,
+}
+
+impl Rule {
+    /// The byte ranges of the text which the rule matches
+    fn find(&self, text: &str) -> Vec<Range<usize>> {
+        self.re
+            .find_iter(text)
+            .filter(|m| {
+                let before = text[..m.start()].chars().next_back();
+                let after = text[m.end()..].chars().next();
+                let (first, last) = (m.as_str().chars().next(), m.as_str().chars().next_back());
+                !self.whole_word || (is_boundary(before, first) && is_boundary(after, last))
+            })
+            .map(|m| m.start()..m.end())
+            .collect()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Check if there is a word boundary between the end of a match and the
+/// character next to it
+///
+/// Unlike `\b` this also works for words which begin or end with symbols
+/// (e.g. `c++`), as no boundary is required next to a symbol.
+fn is_boundary(outside: Option<char>, inside: Option<char>) -> bool {
+    match (outside, inside) {
+        (Some(outside), Some(inside)) => !is_word_char(inside) || !is_word_char(outside),
+        _ => true,
+    }
+}
+
+/// A compiled content filter
+pub struct ContentFilter {
+    rules: Vec<Rule>,
+    max_links: Option<usize>,
+    max_repeated_chars: Option<usize>,
+    classifiers: Vec<Box<dyn Classifier + Send + Sync>>,
+}
+
+impl ContentFilter {
+    /// Compile the rules of the configuration
+    pub fn new(config: &FilterConfig) -> Result<Self, InvalidFilterRule> {
+        let rules = config
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let (pattern, whole_word) = match &rule.pattern {
+                    FilterPattern::Word(word) => (regex::escape(word), true),
+                    FilterPattern::Regex(re) => (re.clone(), false),
+                };
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map(|re| Rule {
+                        re,
+                        action: rule.action,
+                        whole_word,
+                    })
+                    .map_err(|_| InvalidFilterRule { index })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ContentFilter {
+            rules,
+            max_links: config.max_links,
+            max_repeated_chars: config.max_repeated_chars,
+            classifiers: Vec::new(),
+        })
+    }
+
+    /// Add a classifier which is run after the rules and heuristics
+    pub fn with_classifier<C>(mut self, classifier: C) -> Self
+    where
+        C: Classifier + Send + Sync + 'static,
+    {
+        self.classifiers.push(Box::new(classifier));
+        self
+    }
+
+    /// Run the text of e.g. a `Title`, `Description` or `CommentContent`
+    /// through the filter
+    ///
+    /// `text` is the raw text as the user typed it (which is what the fields
+    /// hold), never its HTML escaped form. A masked text can be validated as
+    /// the same field to replace the original.
+    pub fn check(&self, text: &str) -> Verdict {
+        let mut verdict = Verdict::Allow;
+        let mut masked_ranges = Vec::new();
+        for rule in &self.rules {
+            let ranges = rule.find(text);
+            if ranges.is_empty() {
+                continue;
+            }
+            verdict = verdict.merge(match rule.action {
+                FilterAction::Mask => {
+                    masked_ranges.extend(ranges);
+                    Verdict::Mask(String::new())
+                }
+                FilterAction::Hold => Verdict::Hold(FilterReason::BannedWord),
+                FilterAction::Reject => Verdict::Reject(FilterReason::BannedWord),
+            });
+        }
+        if self.max_links.map(|max| count_links(text) > max).unwrap_or(false) {
+            verdict = verdict.merge(Verdict::Hold(FilterReason::TooManyLinks));
+        }
+        if self
+            .max_repeated_chars
+            .map(|max| longest_repeat(text) > max)
+            .unwrap_or(false)
+        {
+            verdict = verdict.merge(Verdict::Hold(FilterReason::RepeatedCharacters));
+        }
+        let mut masked = mask(text, &masked_ranges);
+        for classifier in &self.classifiers {
+            match classifier.classify(&masked) {
+                Verdict::Mask(text) => {
+                    masked = text;
+                    verdict = verdict.merge(Verdict::Mask(String::new()));
+                }
+                other => verdict = verdict.merge(other),
+            }
+        }
+        match verdict {
+            Verdict::Mask(_) => Verdict::Mask(masked),
+            other => other,
+        }
+    }
+
+    /// Run several texts (e.g. the title and description of a thread) through
+    /// the filter, returning the most severe verdict for each
+    pub fn check_all(&self, texts: &[&str]) -> Vec<Verdict> {
+        texts.iter().map(|text| self.check(text)).collect()
+    }
+}
+
+/// Replace every character within the byte ranges with `*`
+fn mask(text: &str, ranges: &[Range<usize>]) -> String {
+    text.char_indices()
+        .map(|(i, c)| {
+            if ranges.iter().any(|r| r.start <= i && i < r.end) {
+                '*'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// The number of links in a text
+pub fn count_links(text: &str) -> usize {
+    lazy_static! {
+        static ref RE: Regex = r"(?i)\b(https?://|www\.)"
+            .parse()
+            .expect("invalid link regex");
+    }
+    RE.find_iter(text).count()
+}
+
+/// The length of the longest run of a single (non-whitespace) character
+pub fn longest_repeat(text: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous = None;
+    for c in text.chars() {
+        if Some(c) == previous && !c.is_whitespace() {
+            current += 1;
+        } else {
+            current = 1;
+        }
+        previous = Some(c);
+        longest = longest.max(current);
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::valid::fields::*;
+    use std::convert::TryFrom;
+
+    fn filter(rules: Vec<(FilterPattern, FilterAction)>) -> ContentFilter {
+        let config = FilterConfig {
+            rules: rules
+                .into_iter()
+                .map(|(pattern, action)| FilterRule { pattern, action })
+                .collect(),
+            ..Default::default()
+        };
+        ContentFilter::new(&config).unwrap()
+    }
+
+    fn comment(s: &str) -> CommentContent {
+        CommentContent::try_from(s.to_owned()).unwrap()
+    }
+
+    fn title(s: &str) -> Title {
+        Title::try_from(s.to_owned()).unwrap()
+    }
+
+    fn description(s: &str) -> Description {
+        Description::try_from(s.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn masks_whole_words() {
+        let f = filter(vec![(FilterPattern::Word("darn".into()), FilterAction::Mask)]);
+        assert_eq!(
+            f.check(&comment("Darn it, darned thing")),
+            Verdict::Mask("**** it, darned thing".into())
+        );
+        assert_eq!(f.check(&comment("nothing to see")), Verdict::Allow);
+    }
+
+    #[test]
+    fn matches_the_text_as_typed() {
+        let f = filter(vec![
+            (FilterPattern::Word("c++".into()), FilterAction::Mask),
+            (FilterPattern::Word("quot".into()), FilterAction::Mask),
+            (FilterPattern::Word("lt".into()), FilterAction::Mask),
+        ]);
+        assert_eq!(
+            f.check(&description("I <3 c++ & \"rust\", not abc++ or quot")),
+            Verdict::Mask("I <3 *** & \"rust\", not abc++ or ****".into())
+        );
+
+        let f = filter(vec![(FilterPattern::Regex("<script".into()), FilterAction::Reject)]);
+        assert_eq!(
+            f.check(&comment("<script>alert</script>")),
+            Verdict::Reject(FilterReason::BannedWord)
+        );
+        assert_eq!(f.check(&comment("script kiddies")), Verdict::Allow);
+        assert_eq!(f.check(&comment("&lt;script> is harmless")), Verdict::Allow);
+    }
+
+    #[test]
+    fn most_severe_action_wins() {
+        let f = filter(vec![
+            (FilterPattern::Word("darn".into()), FilterAction::Mask),
+            (FilterPattern::Regex(r"buy\s+cheap".into()), FilterAction::Reject),
+        ]);
+        let verdict = f.check(&title("darn, BUY  cheap pills"));
+        assert_eq!(verdict, Verdict::Reject(FilterReason::BannedWord));
+        assert_eq!(
+            verdict.error(),
+            Some(ContentError::Rejected {
+                reason: FilterReason::BannedWord
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_regex() {
+        let config = FilterConfig {
+            rules: vec![FilterRule {
+                pattern: FilterPattern::Regex("(".into()),
+                action: FilterAction::Reject,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            ContentFilter::new(&config).err(),
+            Some(InvalidFilterRule { index: 0 })
+        );
+    }
+
+    #[test]
+    fn holds_spammy_content() {
+        let f = filter(Vec::new());
+        let links = "http://a.com https://b.com www.c.com http://d.com http://e.com http://f.com";
+        assert_eq!(
+            f.check(&description(links)),
+            Verdict::Hold(FilterReason::TooManyLinks)
+        );
+        assert_eq!(
+            f.check(&comment("hello!!!!!!!!!!!!")),
+            Verdict::Hold(FilterReason::RepeatedCharacters)
+        );
+        // Symbols are not escaped, so `&&` is a repeat of only two characters
+        assert_eq!(f.check(&comment("this && that && more")), Verdict::Allow);
+    }
+
+    #[test]
+    fn runs_custom_classifiers() {
+        struct Shouting;
+        impl Classifier for Shouting {
+            fn classify(&self, text: &str) -> Verdict {
+                if text.chars().any(|c| c.is_lowercase()) {
+                    Verdict::Allow
+                } else {
+                    Verdict::Hold(FilterReason::Classified)
+                }
+            }
+        }
+        let f = filter(Vec::new()).with_classifier(Shouting);
+        assert_eq!(
+            f.check(&comment("STOP & SHOUT")),
+            Verdict::Hold(FilterReason::Classified)
+        );
+        assert_eq!(f.check(&comment("stop shouting")), Verdict::Allow);
+    }
+}
@@ -0,0 +1,175 @@
+//! Extraction and rendering of `@username` mentions in user generated content
+//!
+//! Mentions are parsed from the already validated text of e.g. a
+//! `CommentContent` or a `Description`. A mention is an `@` followed by a
+//! valid `Username`, which is not preceded by a character that can be part of
+//! a username (so that e.g. email addresses are not treated as mentions).
+
+use crate::valid::fields::Username;
+use crate::valid::ids::UserId;
+use crate::valid::ValidationError;
+use htmlescape::encode_minimal;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::ops::Range;
+
+/// The maximum number of distinct users which can be mentioned in a single
+/// thread or comment
+pub const MAX_MENTIONS: usize = 10;
+
+/// A single mention of a user in some text
+#[derive(PartialEq, Debug, Clone)]
+pub struct Mention {
+    /// The byte range of the mention in the text, including the `@`
+    pub span: Range<usize>,
+    pub username: Username,
+}
+
+/// All the mentions in some text, in the order they appear
+#[derive(PartialEq, Debug, Clone)]
+pub struct Mentions(Vec<Mention>);
+
+impl_deref_and_as_ref!(Mentions => Vec<Mention>);
+impl_into_inner!(Mentions => Vec<Mention>);
+
+impl Mentions {
+    /// Parse all mentions in the text
+    ///
+    /// Fails if more than `MAX_MENTIONS` distinct users are mentioned.
+    pub fn parse(text: &str) -> Result<Mentions, ValidationError> {
+        let mentions = Mentions(find_mentions(text));
+        if mentions.usernames().len() <= MAX_MENTIONS {
+            Ok(mentions)
+        } else {
+            Err(ValidationError::TooManyMentions)
+        }
+    }
+
+    /// Get the distinct usernames which are mentioned, sorted
+    ///
+    /// These are only candidates, as the users might not exist.
+    pub fn usernames(&self) -> Vec<&Username> {
+        let mut usernames: Vec<_> = self.0.iter().map(|m| &m.username).collect();
+        usernames.sort();
+        usernames.dedup();
+        usernames
+    }
+
+    /// Render the mentions in the text as links to the profile of the users
+    ///
+    /// `text` must be the same text the mentions were parsed from. Mentions
+    /// of users which are not present in `resolved` are left as they are.
+    /// The resulting link for a user is given by `profile_url`. The rest of
+    /// the text is HTML escaped.
+    pub fn render<F>(
+        &self,
+        text: &str,
+        resolved: &BTreeMap<Username, UserId>,
+        profile_url: F,
+    ) -> String
+    where
+        F: Fn(UserId) -> String,
+    {
+        let mut rendered = String::with_capacity(text.len());
+        let mut last = 0;
+        for mention in self.0.iter() {
+            if let Some(id) = resolved.get(&mention.username) {
+                rendered.push_str(&encode_minimal(&text[last..mention.span.start]));
+                rendered.push_str(&format!(
+                    "<a href=\"{}\" class=\"mention\">@{}</a>",
+                    profile_url(*id),
+                    mention.username
+                ));
+                last = mention.span.end;
+            }
+        }
+        rendered.push_str(&encode_minimal(&text[last..]));
+        rendered
+    }
+}
+
+/// Check if a byte can be a part of a username
+fn is_username_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'-'
+}
+
+/// Find all the mentions in the text without any limit
+fn find_mentions(text: &str) -> Vec<Mention> {
+    let bytes = text.as_bytes();
+    let mut mentions = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'@' || (i > 0 && is_username_byte(bytes[i - 1])) {
+            i += 1;
+            continue;
+        }
+        let start = i + 1;
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !is_username_byte(*b))
+            .map(|n| start + n)
+            .unwrap_or_else(|| bytes.len());
+        if let Ok(username) = Username::try_from(text[start..end].to_owned()) {
+            mentions.push(Mention {
+                span: i..end,
+                username,
+            });
+        }
+        i = end;
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn username(s: &str) -> Username {
+        Username::try_from(s.to_owned()).expect("invalid username")
+    }
+
+    #[test]
+    fn parses_mentions_with_spans() {
+        let text = "@john and @irene, what about @bo?";
+        let mentions = Mentions::parse(text).unwrap();
+        let found: Vec<_> = mentions
+            .iter()
+            .map(|m| (&text[m.span.clone()], m.username.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("@john", username("john")), ("@irene", username("irene"))]
+        );
+    }
+
+    #[test]
+    fn ignores_emails_and_invalid_usernames() {
+        let text = "mail john@example.com or @waytoolongusername or @@john";
+        let mentions = Mentions::parse(text).unwrap();
+        assert_eq!(mentions.usernames(), vec![&username("john")]);
+    }
+
+    #[test]
+    fn rejects_too_many_mentions() {
+        let text: String = (0..=MAX_MENTIONS).map(|i| format!("@user{} ", i)).collect();
+        assert_eq!(
+            Mentions::parse(&text),
+            Err(ValidationError::TooManyMentions)
+        );
+        let text = "@john ".repeat(MAX_MENTIONS + 1);
+        assert!(Mentions::parse(&text).is_ok());
+    }
+
+    #[test]
+    fn renders_resolved_mentions() {
+        let text = "hi @john & <b>@irene</b>";
+        let mentions = Mentions::parse(text).unwrap();
+        let mut resolved = BTreeMap::new();
+        resolved.insert(username("john"), UserId::from(7));
+        let rendered = mentions.render(text, &resolved, |id| format!("/user/{}", id));
+        assert_eq!(
+            rendered,
+            "hi <a href=\"/user/7\" class=\"mention\">@john</a> &amp; &lt;b&gt;@irene&lt;/b&gt;"
+        );
+    }
+}
@@ -1,4 +1,7 @@
 //! Content requests from and responses to the web-client
 
+pub mod filter;
+pub mod mentions;
+pub mod ranking;
 pub mod requests;
 pub mod responses;
@@ -0,0 +1,137 @@
+//! Helpers to rank threads and comments by their votes
+
+use chrono::naive::NaiveDateTime;
+use crate::content::responses::{CommentPayload, ThreadPayload};
+use std::cmp::Ordering;
+
+/// The z-score used for the Wilson score interval (95% confidence)
+const WILSON_Z: f64 = 1.96;
+
+/// The point in time which the hot rank is measured from (in seconds since
+/// the unix epoch)
+const HOT_RANK_EPOCH: i64 = 1_134_028_003;
+
+/// The number of seconds it takes for content to lose the equivalent of a
+/// tenfold increase of its score
+const HOT_RANK_DECAY: f64 = 45_000.0;
+
+/// Content which can be ranked by its votes
+pub trait Rankable {
+    /// The number of upvotes the content has received
+    fn upvotes(&self) -> u32;
+
+    /// The number of downvotes the content has received
+    fn downvotes(&self) -> u32;
+
+    /// The time the content was created
+    fn created(&self) -> NaiveDateTime;
+
+    /// The net score of the content
+    fn score(&self) -> i64 {
+        i64::from(self.upvotes()) - i64::from(self.downvotes())
+    }
+
+    /// See [`wilson_score`](fn.wilson_score.html)
+    fn wilson_score(&self) -> f64 {
+        wilson_score(self.upvotes(), self.downvotes())
+    }
+
+    /// See [`hot_rank`](fn.hot_rank.html)
+    fn hot_rank(&self) -> f64 {
+        hot_rank(self.upvotes(), self.downvotes(), self.created())
+    }
+}
+
+impl Rankable for ThreadPayload {
+    fn upvotes(&self) -> u32 {
+        self.upvotes
+    }
+    fn downvotes(&self) -> u32 {
+        self.downvotes
+    }
+    fn created(&self) -> NaiveDateTime {
+        self.timestamp
+    }
+}
+
+impl Rankable for CommentPayload {
+    fn upvotes(&self) -> u32 {
+        self.upvotes
+    }
+    fn downvotes(&self) -> u32 {
+        self.downvotes
+    }
+    fn created(&self) -> NaiveDateTime {
+        self.timestamp
+    }
+}
+
+/// The lower bound of the Wilson score confidence interval
+///
+/// Suitable to rank content by quality independent of time, as content with
+/// few votes is not ranked above content with many mostly positive votes.
+pub fn wilson_score(upvotes: u32, downvotes: u32) -> f64 {
+    let n = f64::from(upvotes) + f64::from(downvotes);
+    if n == 0.0 {
+        return 0.0;
+    }
+    let p = f64::from(upvotes) / n;
+    let z2 = WILSON_Z * WILSON_Z;
+    let spread = WILSON_Z * ((p * (1.0 - p) + z2 / (4.0 * n)) / n).sqrt();
+    (p + z2 / (2.0 * n) - spread) / (1.0 + z2 / n)
+}
+
+/// A rank which combines the score of content with its age
+///
+/// Newer content is ranked higher than older content with the same score,
+/// which makes it suitable to sort a "hot" listing.
+pub fn hot_rank(upvotes: u32, downvotes: u32, created: NaiveDateTime) -> f64 {
+    let score = i64::from(upvotes) - i64::from(downvotes);
+    let order = (score.abs().max(1) as f64).log10();
+    let sign = score.signum() as f64;
+    let seconds = (created.timestamp() - HOT_RANK_EPOCH) as f64;
+    sign * order + seconds / HOT_RANK_DECAY
+}
+
+/// Sort content by the Wilson score, best first
+pub fn sort_by_wilson_score<T: Rankable>(items: &mut [T]) {
+    items.sort_by(|a, b| {
+        b.wilson_score()
+            .partial_cmp(&a.wilson_score())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Sort content by the hot rank, hottest first
+pub fn sort_by_hot_rank<T: Rankable>(items: &mut [T]) {
+    items.sort_by(|a, b| {
+        b.hot_rank()
+            .partial_cmp(&a.hot_rank())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+
+    #[test]
+    fn wilson_score_without_votes_is_zero() {
+        assert_eq!(wilson_score(0, 0), 0.0);
+    }
+
+    #[test]
+    fn wilson_score_prefers_more_votes() {
+        assert!(wilson_score(100, 10) > wilson_score(1, 0));
+        assert!(wilson_score(10, 0) > wilson_score(10, 5));
+    }
+
+    #[test]
+    fn hot_rank_prefers_newer_content() {
+        let old = NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0);
+        let new = NaiveDate::from_ymd(2018, 10, 2).and_hms(12, 0, 0);
+        assert!(hot_rank(10, 0, new) > hot_rank(10, 0, old));
+        assert!(hot_rank(10, 0, old) > hot_rank(0, 10, old));
+    }
+}
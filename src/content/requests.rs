@@ -1,7 +1,12 @@
 //! The requests a user can make to the content-database
 
+use chrono::naive::NaiveDateTime;
+use crate::attachments::responses::AttachmentRef;
+use crate::auth::responses::Role;
+use crate::content::responses::{PrivacySettings, ReportStatus};
 use crate::valid::fields::*;
 use crate::valid::ids::*;
+use crate::valid::ValidationError;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(
@@ -16,14 +21,35 @@ pub enum ContentRequest {
     AddCategory(AddCategoryPayload),
     EditCategory(EditCategoryPayload),
     HideCategory(HideCategoryPayload),
+    MoveCategory(MoveCategoryPayload),
+    ReorderCategories(ReorderCategoriesPayload),
 
     AddThread(AddThreadPayload),
     EditThread(EditThreadPayload),
     HideThread(HideThreadPayload),
+    PinThread(PinThreadPayload),
+    LockThread(LockThreadPayload),
+    ArchiveThread(ArchiveThreadPayload),
+    MoveThread(MoveThreadPayload),
 
     AddComment(AddCommentPayload),
     EditComment(EditCommentPayload),
     HideComment(HideCommentPayload),
+
+    Vote(VotePayload),
+    React(ReactPayload),
+
+    ReportContent(ReportContentPayload),
+
+    CastVote(CastVotePayload),
+
+    Bookmark(BookmarkPayload),
+    Unbookmark(BookmarkPayload),
+    MarkThreadRead(MarkThreadReadPayload),
+
+    SaveDraft(SaveDraftPayload),
+    DeleteDraft(DraftIdPayload),
+    PublishDraft(DraftIdPayload),
 }
 
 // Users
@@ -42,8 +68,13 @@ pub struct AddUserPayload {
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct EditUserPayload {
     pub id: Option<UserId>,
+    pub display_name: Option<DisplayName>,
     pub description: Option<Description>,
     pub avatar: Option<String>,
+    pub signature: Option<Description>,
+    pub location: Option<Location>,
+    pub website: Option<Website>,
+    pub privacy: Option<PrivacySettings>,
 }
 
 // Categories
@@ -56,8 +87,13 @@ pub struct GetCategoryPayload {
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct AddCategoryPayload {
+    pub parent_id: Option<CategoryId>,
     pub title: Title,
     pub description: Description,
+    #[serde(default)]
+    pub view_role: Role,
+    #[serde(default)]
+    pub post_role: Role,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -65,6 +101,8 @@ pub struct EditCategoryPayload {
     pub id: CategoryId,
     pub title: Option<Title>,
     pub description: Option<Description>,
+    pub view_role: Option<Role>,
+    pub post_role: Option<Role>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -73,6 +111,22 @@ pub struct HideCategoryPayload {
     pub hide: bool,
 }
 
+/// Move a category below another category, or to the top level if
+/// `parent_id` is `None`
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MoveCategoryPayload {
+    pub id: CategoryId,
+    pub parent_id: Option<CategoryId>,
+}
+
+/// Set the display order of all the children of a category (or of the top
+/// level categories if `parent_id` is `None`)
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReorderCategoriesPayload {
+    pub parent_id: Option<CategoryId>,
+    pub order: Vec<CategoryId>,
+}
+
 // Threads
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -87,12 +141,23 @@ pub struct GetThreadsPayload {
     pub include_hidden: bool,
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetThreadsByTagPayload {
+    pub tag: TagName,
+    pub include_hidden: bool,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct AddThreadPayload {
     pub category_id: CategoryId,
     pub user_id: Option<UserId>,
     pub title: Title,
     pub description: Description,
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub tags: Vec<TagName>,
+    #[serde(default)]
+    pub attachments: Vec<AttachmentRef>,
+    pub poll: Option<AddPollPayload>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -101,6 +166,8 @@ pub struct EditThreadPayload {
     pub user_id: Option<UserId>,
     pub title: Option<Title>,
     pub description: Option<Description>,
+    #[serde(default, deserialize_with = "deserialize_optional_tags")]
+    pub tags: Option<Vec<TagName>>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -110,6 +177,98 @@ pub struct HideThreadPayload {
     pub hide: bool,
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct PinThreadPayload {
+    pub id: ThreadId,
+    pub user_id: Option<UserId>,
+    pub pin: bool,
+    pub reason: Reason,
+}
+
+/// Lock or unlock a thread, a locked thread does not accept new comments
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct LockThreadPayload {
+    pub id: ThreadId,
+    pub user_id: Option<UserId>,
+    pub lock: bool,
+    pub reason: Reason,
+}
+
+/// Archive or unarchive a thread, an archived thread is read-only
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ArchiveThreadPayload {
+    pub id: ThreadId,
+    pub user_id: Option<UserId>,
+    pub archive: bool,
+    pub reason: Reason,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MoveThreadPayload {
+    pub id: ThreadId,
+    pub user_id: Option<UserId>,
+    pub category_id: CategoryId,
+    pub reason: Reason,
+}
+
+// Polls
+
+/// A poll which is attached to a new thread
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct AddPollPayload {
+    pub question: Title,
+    #[serde(deserialize_with = "deserialize_poll_options")]
+    pub options: Vec<PollOption>,
+    pub multiple_choice: bool,
+    pub closes_at: Option<NaiveDateTime>,
+}
+
+/// Vote on the poll of a thread, `options` are indices into the options of
+/// the poll
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct CastVotePayload {
+    pub thread_id: ThreadId,
+    pub user_id: Option<UserId>,
+    pub options: Vec<u32>,
+}
+
+// Tags
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetTagsPayload {
+    pub limit: Option<u32>,
+}
+
+/// Get the tags which start with the given prefix, most used first
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct AutocompleteTagsPayload {
+    pub prefix: TagPrefix,
+    pub limit: Option<u32>,
+}
+
+// Bookmarks and read markers
+
+/// Get the threads a user has bookmarked
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ListBookmarksPayload {
+    pub user_id: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BookmarkPayload {
+    pub thread_id: ThreadId,
+    pub user_id: Option<UserId>,
+}
+
+/// Mark all comments up to and including `last_comment_id` in a thread as
+/// read, `None` marks a thread without comments as read
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MarkThreadReadPayload {
+    pub thread_id: ThreadId,
+    pub user_id: Option<UserId>,
+    pub last_comment_id: Option<CommentId>,
+}
+
 // Comments
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -130,6 +289,8 @@ pub struct AddCommentPayload {
     pub user_id: Option<UserId>,
     pub parent_id: Option<CommentId>,
     pub content: CommentContent,
+    #[serde(default)]
+    pub attachments: Vec<AttachmentRef>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -146,6 +307,142 @@ pub struct HideCommentPayload {
     pub hide: bool,
 }
 
+// Votes and reactions
+
+/// The direction of a vote, `Neutral` retracts a previous vote
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VoteDirection {
+    Up,
+    Down,
+    Neutral,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct VotePayload {
+    pub target: ContentRef,
+    pub user_id: Option<UserId>,
+    pub direction: VoteDirection,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReactPayload {
+    pub target: ContentRef,
+    pub user_id: Option<UserId>,
+    pub emoji: Emoji,
+    pub react: bool,
+}
+
+// Reports
+
+/// The category of abuse a report is about
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    HateSpeech,
+    Inappropriate,
+    OffTopic,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReportContentPayload {
+    pub target: ContentRef,
+    pub user_id: Option<UserId>,
+    pub reason: ReportReason,
+    pub text: Option<Description>,
+}
+
+/// Get the reports for moderators, optionally only those with a given status
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetReportsPayload {
+    pub status: Option<ReportStatus>,
+}
+
+// Drafts
+
+/// The contents of a thread or a comment which is not yet published
+///
+/// The text fields are only loosely validated until the draft is published.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum Draft {
+    Thread {
+        category_id: CategoryId,
+        title: Incomplete<Title>,
+        description: Incomplete<Description>,
+    },
+    Comment {
+        thread_id: ThreadId,
+        parent_id: Option<CommentId>,
+        content: Incomplete<CommentContent>,
+    },
+}
+
+/// A draft which has been validated and can be added as content
+#[derive(PartialEq, Debug)]
+pub enum PublishedDraft {
+    Thread(AddThreadPayload),
+    Comment(AddCommentPayload),
+}
+
+impl Draft {
+    /// Validate the draft as a complete thread or comment
+    pub fn publish(self, user_id: Option<UserId>) -> Result<PublishedDraft, ValidationError> {
+        match self {
+            Draft::Thread {
+                category_id,
+                title,
+                description,
+            } => Ok(PublishedDraft::Thread(AddThreadPayload {
+                category_id,
+                user_id,
+                title: title.complete()?,
+                description: description.complete()?,
+                tags: Vec::new(),
+                attachments: Vec::new(),
+                poll: None,
+            })),
+            Draft::Comment {
+                thread_id,
+                parent_id,
+                content,
+            } => Ok(PublishedDraft::Comment(AddCommentPayload {
+                thread_id,
+                user_id,
+                parent_id,
+                content: content.complete()?,
+                attachments: Vec::new(),
+            })),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct ListDraftsPayload {
+    pub user_id: Option<UserId>,
+}
+
+/// Save a new draft, or overwrite an existing draft if `id` is present
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SaveDraftPayload {
+    pub id: Option<DraftId>,
+    pub user_id: Option<UserId>,
+    pub draft: Draft,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct DraftIdPayload {
+    pub id: DraftId,
+    pub user_id: Option<UserId>,
+}
+
 // Search
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
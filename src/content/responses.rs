@@ -1,6 +1,11 @@
 //! The responses a user will get on requests to the content-database
 
 use chrono::naive::NaiveDateTime;
+use crate::admin::requests::ModerationAction;
+use crate::attachments::responses::AttachmentRef;
+use crate::auth::responses::Role;
+use crate::content::filter::FilterReason;
+use crate::content::requests::{Draft, ReportReason};
 use crate::valid::fields::*;
 use crate::valid::ids::*;
 
@@ -18,9 +23,16 @@ pub enum ContentSuccess {
     Threads(Vec<ThreadPayload>),
     Comment(CommentPayload),
     Comments(Vec<CommentPayload>),
-    User(UserPayload),
-    Users(Vec<UserPayload>),
+    User(PublicUserPayload),
+    Users(Vec<PublicUserPayload>),
+    PrivateUser(PrivateUserPayload),
     SearchResult(SearchResultsPayload),
+    Score(ScorePayload),
+    Tags(Vec<TagPayload>),
+    Report(ReportPayload),
+    Reports(Vec<ReportPayload>),
+    Draft(DraftPayload),
+    Drafts(Vec<DraftPayload>),
 }
 
 /// All the unsuccessful responses to a `ContentRequest`
@@ -38,22 +50,198 @@ pub enum ContentError {
     InvalidId,
     #[fail(display = "invalid content")]
     InvalidContent,
+    #[fail(display = "thread is locked")]
+    ThreadLocked,
+    #[fail(display = "thread is archived")]
+    ThreadArchived,
+    #[fail(display = "content is already reported by user")]
+    AlreadyReported,
+    #[fail(display = "poll is closed")]
+    PollClosed,
+    #[fail(display = "user has already voted in poll")]
+    AlreadyVoted,
+    #[fail(display = "invalid choice of poll options")]
+    InvalidPollVote,
+    #[fail(display = "draft is not complete")]
+    IncompleteDraft,
+    #[fail(display = "category cannot be its own ancestor")]
+    CategoryCycle,
+    #[fail(display = "user is banned")]
+    Banned,
+    #[fail(display = "user is suspended until {}", until)]
+    Suspended { until: NaiveDateTime },
+    #[fail(display = "user is muted")]
+    Muted { until: Option<NaiveDateTime> },
+    #[fail(display = "content was rejected by the content filter")]
+    Rejected { reason: FilterReason },
+    #[fail(display = "content is held for review by a moderator")]
+    HeldForReview { reason: FilterReason },
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
-pub struct UserPayload {
+/// Who can see a field of a profile
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Visibility {
+    Everyone,
+    /// Only authenticated users
+    Members,
+    /// Only the user and moderators
+    Nobody,
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Visibility::Everyone
+    }
+}
+
+/// The visibility of the optional fields of a profile
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct PrivacySettings {
+    pub location: Visibility,
+    pub website: Visibility,
+    pub post_counts: Visibility,
+}
+
+/// The user who is viewing a profile
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Viewer {
+    Anonymous,
+    Member(UserId, Role),
+}
+
+impl Viewer {
+    /// Check if the viewer can see a field of the profile of `owner`
+    pub fn can_see(self, owner: UserId, visibility: Visibility) -> bool {
+        match (visibility, self) {
+            (Visibility::Everyone, _) => true,
+            (_, Viewer::Anonymous) => false,
+            (Visibility::Members, Viewer::Member(..)) => true,
+            (Visibility::Nobody, Viewer::Member(id, role)) => {
+                id == owner || role.has(Role::Moderator)
+            }
+        }
+    }
+}
+
+/// The profile of a user as it is shown to other users
+///
+/// The optional fields are `None` if they are empty or if the privacy
+/// settings of the user hide them from the viewer.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PublicUserPayload {
     pub id: UserId,
     pub username: Username,
+    pub display_name: Option<DisplayName>,
     pub description: Option<Description>,
     pub avatar: Option<String>,
+    pub signature: Option<Description>,
+    pub location: Option<Location>,
+    pub website: Option<Website>,
+    pub role: Role,
+    pub joined: NaiveDateTime,
+    pub thread_count: Option<u32>,
+    pub comment_count: Option<u32>,
+}
+
+/// The full profile of a user, which is only sent to the user themselves
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PrivateUserPayload {
+    #[serde(flatten)]
+    pub profile: PublicUserPayload,
+    pub email: Option<Email>,
+    pub privacy: PrivacySettings,
+}
+
+impl PrivateUserPayload {
+    /// Get the profile as seen by the viewer, with the fields hidden by the
+    /// privacy settings removed
+    pub fn to_public(&self, viewer: Viewer) -> PublicUserPayload {
+        let id = self.profile.id;
+        let can_see = |visibility| viewer.can_see(id, visibility);
+        let mut profile = self.profile.clone();
+        if !can_see(self.privacy.location) {
+            profile.location = None;
+        }
+        if !can_see(self.privacy.website) {
+            profile.website = None;
+        }
+        if !can_see(self.privacy.post_counts) {
+            profile.thread_count = None;
+            profile.comment_count = None;
+        }
+        profile
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct CategoryPayload {
     pub id: CategoryId,
+    pub parent_id: Option<CategoryId>,
+    /// The display order of the category among its siblings
+    pub position: u32,
     pub title: Title,
     pub description: Description,
     pub hidden: bool,
+    /// The minimum role required to view the category
+    pub view_role: Role,
+    /// The minimum role required to post in the category
+    pub post_role: Role,
+    pub thread_count: u32,
+    pub comment_count: u32,
+    pub last_activity: Option<LastActivityPayload>,
+}
+
+impl CategoryPayload {
+    pub fn can_view(&self, role: Role) -> bool {
+        role.has(self.view_role)
+    }
+
+    pub fn can_post(&self, role: Role) -> bool {
+        self.can_view(role) && role.has(self.post_role)
+    }
+
+    /// Replace the deleted user with the tombstone user, if they posted the
+    /// latest activity
+    pub fn anonymize(&mut self, deleted: UserId) {
+        if let Some(last_activity) = &mut self.last_activity {
+            last_activity.user_id.anonymize(deleted);
+        }
+    }
+}
+
+/// The latest thread or comment which was posted in a category
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct LastActivityPayload {
+    pub thread_id: ThreadId,
+    pub comment_id: Option<CommentId>,
+    pub user_id: UserId,
+    pub timestamp: NaiveDateTime,
+}
+
+/// Vertify that moving the category `id` below `parent_id` does not make the
+/// category its own ancestor
+///
+/// `categories` must contain all the ancestors of `parent_id`.
+pub fn check_category_move(
+    categories: &[CategoryPayload],
+    id: CategoryId,
+    parent_id: Option<CategoryId>,
+) -> Result<(), ContentError> {
+    let mut ancestor = parent_id;
+    let mut depth = 0;
+    while let Some(ancestor_id) = ancestor {
+        if ancestor_id == id || depth > categories.len() {
+            return Err(ContentError::CategoryCycle);
+        }
+        ancestor = categories
+            .iter()
+            .find(|c| c.id == ancestor_id)
+            .ok_or(ContentError::MissingContent)?
+            .parent_id;
+        depth += 1;
+    }
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -63,8 +251,101 @@ pub struct ThreadPayload {
     pub user_id: UserId,
     pub title: Title,
     pub description: Description,
+    pub tags: Vec<TagName>,
+    pub attachments: Vec<AttachmentRef>,
     pub timestamp: NaiveDateTime,
     pub hidden: bool,
+    pub state: ThreadState,
+    pub poll: Option<PollPayload>,
+    /// If the requesting user has bookmarked the thread
+    pub bookmarked: bool,
+    /// The number of comments the requesting user has not read
+    pub unread_count: u32,
+    /// The first comment the requesting user has not read
+    pub first_unread_id: Option<CommentId>,
+    pub upvotes: u32,
+    pub downvotes: u32,
+    pub reactions: Vec<ReactionCountPayload>,
+}
+
+impl ThreadPayload {
+    /// Replace the deleted user with the tombstone user, if they are the
+    /// author of the thread
+    pub fn anonymize(&mut self, deleted: UserId) {
+        self.user_id.anonymize(deleted);
+    }
+}
+
+/// The moderation state of a thread
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct ThreadState {
+    pub pinned: bool,
+    pub locked: bool,
+    pub archived: bool,
+    /// The category the thread was moved from by a moderator, if any
+    pub moved_from: Option<CategoryId>,
+}
+
+impl ThreadState {
+    /// Vertify that new comments can be added to a thread in this state
+    pub fn check_commentable(&self) -> Result<(), ContentError> {
+        if self.archived {
+            Err(ContentError::ThreadArchived)
+        } else if self.locked {
+            Err(ContentError::ThreadLocked)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A poll with the current results, as seen by the user who requested it
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PollPayload {
+    pub question: Title,
+    pub options: Vec<PollOptionPayload>,
+    pub multiple_choice: bool,
+    pub closes_at: Option<NaiveDateTime>,
+    /// The number of users which have voted
+    pub voters: u32,
+    /// If the requesting user has voted
+    pub voted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PollOptionPayload {
+    pub text: PollOption,
+    pub votes: u32,
+}
+
+impl PollPayload {
+    /// Check if the poll is closed at the given time
+    pub fn is_closed(&self, now: NaiveDateTime) -> bool {
+        self.closes_at.map(|closes_at| closes_at <= now).unwrap_or(false)
+    }
+
+    /// Vertify that the requesting user can vote for the given options
+    pub fn check_vote(&self, options: &[u32], now: NaiveDateTime) -> Result<(), ContentError> {
+        if self.is_closed(now) {
+            return Err(ContentError::PollClosed);
+        }
+        if self.voted {
+            return Err(ContentError::AlreadyVoted);
+        }
+        let in_range = options
+            .iter()
+            .all(|i| (*i as usize) < self.options.len());
+        let is_unique = options
+            .iter()
+            .enumerate()
+            .all(|(i, option)| !options[..i].contains(option));
+        let count_ok = !options.is_empty() && (self.multiple_choice || options.len() == 1);
+        if in_range && is_unique && count_ok {
+            Ok(())
+        } else {
+            Err(ContentError::InvalidPollVote)
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -74,8 +355,92 @@ pub struct CommentPayload {
     pub parent_id: Option<CommentId>,
     pub user_id: UserId,
     pub content: CommentContent,
+    pub attachments: Vec<AttachmentRef>,
     pub timestamp: NaiveDateTime,
     pub hidden: bool,
+    pub upvotes: u32,
+    pub downvotes: u32,
+    pub reactions: Vec<ReactionCountPayload>,
+}
+
+impl CommentPayload {
+    /// Replace the deleted user with the tombstone user, if they are the
+    /// author of the comment
+    pub fn anonymize(&mut self, deleted: UserId) {
+        self.user_id.anonymize(deleted);
+    }
+}
+
+/// A tag and the number of threads which are tagged with it
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TagPayload {
+    pub name: TagName,
+    pub thread_count: u32,
+}
+
+/// The number of times a single emoji has been used to react to some content
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ReactionCountPayload {
+    pub emoji: Emoji,
+    pub count: u32,
+}
+
+/// The current votes and reactions of a thread or a comment
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ScorePayload {
+    pub target: ContentRef,
+    pub upvotes: u32,
+    pub downvotes: u32,
+    pub reactions: Vec<ReactionCountPayload>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DraftPayload {
+    pub id: DraftId,
+    pub user_id: UserId,
+    pub draft: Draft,
+    pub timestamp: NaiveDateTime,
+}
+
+/// The status of a report in the moderation queue
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReportStatus {
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ReportPayload {
+    pub id: ReportId,
+    pub target: ContentRef,
+    pub reporter_id: UserId,
+    pub reason: ReportReason,
+    pub text: Option<Description>,
+    pub status: ReportStatus,
+    pub timestamp: NaiveDateTime,
+    pub resolution: Option<ReportResolutionPayload>,
+}
+
+impl ReportPayload {
+    /// Replace the deleted user with the tombstone user, if they reported the
+    /// content
+    ///
+    /// The moderator who resolved the report is kept, so moderation actions
+    /// stay accountable.
+    pub fn anonymize(&mut self, deleted: UserId) {
+        self.reporter_id.anonymize(deleted);
+    }
+}
+
+/// How a moderator handled a report which is no longer open
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ReportResolutionPayload {
+    pub moderator_id: UserId,
+    pub reason: Reason,
+    pub actions: Vec<ModerationAction>,
+    pub timestamp: NaiveDateTime,
 }
 
 /// A search result which contains all the elements that matched the search
@@ -84,5 +449,173 @@ pub struct SearchResultsPayload {
     pub categories: Vec<CategoryPayload>,
     pub threads: Vec<ThreadPayload>,
     pub comments: Vec<CommentPayload>,
-    pub users: Vec<UserPayload>,
+    pub users: Vec<PublicUserPayload>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use std::convert::TryFrom;
+
+    fn poll(multiple_choice: bool, voted: bool) -> PollPayload {
+        let option = |s: &str| PollOptionPayload {
+            text: PollOption::try_from(s.to_owned()).unwrap(),
+            votes: 0,
+        };
+        PollPayload {
+            question: Title::try_from("Which one?".to_owned()).unwrap(),
+            options: vec![option("First"), option("Second"), option("Third")],
+            multiple_choice,
+            closes_at: Some(NaiveDate::from_ymd(2018, 10, 2).and_hms(12, 0, 0)),
+            voters: 0,
+            voted,
+        }
+    }
+
+    #[test]
+    fn poll_vote_validation() {
+        let before = NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0);
+        let after = NaiveDate::from_ymd(2018, 10, 3).and_hms(12, 0, 0);
+
+        assert_eq!(poll(false, false).check_vote(&[1], before), Ok(()));
+        assert_eq!(poll(true, false).check_vote(&[0, 2], before), Ok(()));
+        assert_eq!(
+            poll(false, false).check_vote(&[1], after),
+            Err(ContentError::PollClosed)
+        );
+        assert_eq!(
+            poll(false, true).check_vote(&[1], before),
+            Err(ContentError::AlreadyVoted)
+        );
+        for options in [&[][..], &[0, 1], &[3]].iter() {
+            assert_eq!(
+                poll(false, false).check_vote(options, before),
+                Err(ContentError::InvalidPollVote)
+            );
+        }
+        assert_eq!(
+            poll(true, false).check_vote(&[1, 1], before),
+            Err(ContentError::InvalidPollVote)
+        );
+    }
+
+    #[test]
+    fn visibility_for_each_viewer() {
+        let owner = UserId::from(1);
+        let anonymous = Viewer::Anonymous;
+        let member = Viewer::Member(UserId::from(2), Role::User);
+        let own_profile = Viewer::Member(owner, Role::User);
+        let moderator = Viewer::Member(UserId::from(3), Role::Moderator);
+        let expected = [
+            (Visibility::Everyone, [true, true, true, true]),
+            (Visibility::Members, [false, true, true, true]),
+            (Visibility::Nobody, [false, false, true, true]),
+        ];
+        let viewers = [anonymous, member, own_profile, moderator];
+        for (visibility, expected) in expected.iter() {
+            for (viewer, can_see) in viewers.iter().zip(expected) {
+                assert_eq!(
+                    viewer.can_see(owner, *visibility),
+                    *can_see,
+                    "{:?} viewing a field visible to {:?}",
+                    viewer,
+                    visibility
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn public_profile_hides_private_fields() {
+        let user = PrivateUserPayload {
+            profile: PublicUserPayload {
+                id: UserId::from(1),
+                username: Username::try_from("john".to_owned()).unwrap(),
+                display_name: None,
+                description: None,
+                avatar: None,
+                signature: None,
+                location: Some(Location::try_from("Oslo".to_owned()).unwrap()),
+                website: Some(Website::try_from("https://example.com".to_owned()).unwrap()),
+                role: Role::User,
+                joined: NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0),
+                thread_count: Some(3),
+                comment_count: Some(7),
+            },
+            email: None,
+            privacy: PrivacySettings {
+                location: Visibility::Everyone,
+                website: Visibility::Members,
+                post_counts: Visibility::Nobody,
+            },
+        };
+
+        let anonymous = user.to_public(Viewer::Anonymous);
+        assert!(anonymous.location.is_some());
+        assert!(anonymous.website.is_none());
+        assert!(anonymous.thread_count.is_none() && anonymous.comment_count.is_none());
+
+        let member = user.to_public(Viewer::Member(UserId::from(2), Role::User));
+        assert!(member.location.is_some() && member.website.is_some());
+        assert!(member.thread_count.is_none() && member.comment_count.is_none());
+
+        let owner = user.to_public(Viewer::Member(UserId::from(1), Role::User));
+        assert_eq!(owner, user.profile);
+
+        let moderator = user.to_public(Viewer::Member(UserId::from(3), Role::Moderator));
+        assert_eq!(moderator, user.profile);
+    }
+
+    fn category(id: u32, parent_id: Option<u32>) -> CategoryPayload {
+        CategoryPayload {
+            id: CategoryId::from(id),
+            parent_id: parent_id.map(CategoryId::from),
+            position: 0,
+            title: Title::try_from(format!("Category {}", id)).unwrap(),
+            description: Description::try_from(String::new()).unwrap(),
+            hidden: false,
+            view_role: Role::User,
+            post_role: Role::User,
+            thread_count: 0,
+            comment_count: 0,
+            last_activity: None,
+        }
+    }
+
+    #[test]
+    fn category_moves_cannot_create_cycles() {
+        // 1 -> 2 -> 3 -> 4, and 5 on its own
+        let categories = vec![
+            category(1, None),
+            category(2, Some(1)),
+            category(3, Some(2)),
+            category(4, Some(3)),
+            category(5, None),
+        ];
+        let id = CategoryId::from;
+
+        assert_eq!(check_category_move(&categories, id(2), Some(id(5))), Ok(()));
+        assert_eq!(check_category_move(&categories, id(4), None), Ok(()));
+        assert_eq!(check_category_move(&categories, id(5), Some(id(4))), Ok(()));
+        assert_eq!(
+            check_category_move(&categories, id(3), Some(id(3))),
+            Err(ContentError::CategoryCycle)
+        );
+        assert_eq!(
+            check_category_move(&categories, id(1), Some(id(4))),
+            Err(ContentError::CategoryCycle)
+        );
+        assert_eq!(
+            check_category_move(&categories, id(5), Some(id(9))),
+            Err(ContentError::MissingContent)
+        );
+
+        // An ancestor of the new parent is missing
+        let categories = vec![category(1, Some(9)), category(2, None)];
+        assert_eq!(
+            check_category_move(&categories, CategoryId::from(2), Some(CategoryId::from(1))),
+            Err(ContentError::MissingContent)
+        );
+    }
 }
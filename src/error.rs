@@ -1,8 +1,12 @@
 //! A collection of all common errors
 
 use crate::admin::responses::AdminError;
+use crate::attachments::responses::AttachmentError;
 use crate::auth::responses::AuthError;
 use crate::content::responses::ContentError;
+use crate::messages::responses::MessageError;
+use crate::notifications::responses::NotificationError;
+use crate::settings::responses::SettingsError;
 
 pub type ResponseResult<T> = Result<T, ResponseError>;
 
@@ -19,6 +23,14 @@ pub enum ResponseError {
     ContentRequestError(#[cause] ContentError),
     #[fail(display = "error specific to admin requests")]
     AdminRequestError(#[cause] AdminError),
+    #[fail(display = "error specific to message requests")]
+    MessageRequestError(#[cause] MessageError),
+    #[fail(display = "error specific to notification requests")]
+    NotificationRequestError(#[cause] NotificationError),
+    #[fail(display = "error specific to attachment requests")]
+    AttachmentRequestError(#[cause] AttachmentError),
+    #[fail(display = "error specific to settings requests")]
+    SettingsRequestError(#[cause] SettingsError),
     #[fail(display = "user is not authenticated with the service")]
     Unauthenticated,
     #[fail(display = "user is not authorized to perform action")]
@@ -47,6 +59,56 @@ impl From<tarpc::Error<AuthError>> for ResponseError {
     }
 }
 
+impl From<tarpc::Error<AdminError>> for ResponseError {
+    fn from(e: tarpc::Error<AdminError>) -> ResponseError {
+        let ee: AdminError = e.into();
+        match ee {
+            AdminError::InternalError => ResponseError::InternalServerError,
+            eee => ResponseError::AdminRequestError(eee),
+        }
+    }
+}
+
+impl From<tarpc::Error<MessageError>> for ResponseError {
+    fn from(e: tarpc::Error<MessageError>) -> ResponseError {
+        let ee: MessageError = e.into();
+        match ee {
+            MessageError::InternalServerError => ResponseError::InternalServerError,
+            eee => ResponseError::MessageRequestError(eee),
+        }
+    }
+}
+
+impl From<tarpc::Error<NotificationError>> for ResponseError {
+    fn from(e: tarpc::Error<NotificationError>) -> ResponseError {
+        let ee: NotificationError = e.into();
+        match ee {
+            NotificationError::InternalServerError => ResponseError::InternalServerError,
+            eee => ResponseError::NotificationRequestError(eee),
+        }
+    }
+}
+
+impl From<tarpc::Error<AttachmentError>> for ResponseError {
+    fn from(e: tarpc::Error<AttachmentError>) -> ResponseError {
+        let ee: AttachmentError = e.into();
+        match ee {
+            AttachmentError::InternalServerError => ResponseError::InternalServerError,
+            eee => ResponseError::AttachmentRequestError(eee),
+        }
+    }
+}
+
+impl From<tarpc::Error<SettingsError>> for ResponseError {
+    fn from(e: tarpc::Error<SettingsError>) -> ResponseError {
+        let ee: SettingsError = e.into();
+        match ee {
+            SettingsError::InternalServerError => ResponseError::InternalServerError,
+            eee => ResponseError::SettingsRequestError(eee),
+        }
+    }
+}
+
 impl From<tarpc::Error<ContentError>> for ContentError {
     fn from(e: tarpc::Error<ContentError>) -> ContentError {
         use tarpc::Error::*;
@@ -67,6 +129,56 @@ impl From<tarpc::Error<AuthError>> for AuthError {
     }
 }
 
+impl From<tarpc::Error<AdminError>> for AdminError {
+    fn from(e: tarpc::Error<AdminError>) -> AdminError {
+        use tarpc::Error::*;
+        match e {
+            App(ee) => ee,
+            _ => AdminError::InternalError,
+        }
+    }
+}
+
+impl From<tarpc::Error<MessageError>> for MessageError {
+    fn from(e: tarpc::Error<MessageError>) -> MessageError {
+        use tarpc::Error::*;
+        match e {
+            App(ee) => ee,
+            _ => MessageError::InternalServerError,
+        }
+    }
+}
+
+impl From<tarpc::Error<NotificationError>> for NotificationError {
+    fn from(e: tarpc::Error<NotificationError>) -> NotificationError {
+        use tarpc::Error::*;
+        match e {
+            App(ee) => ee,
+            _ => NotificationError::InternalServerError,
+        }
+    }
+}
+
+impl From<tarpc::Error<AttachmentError>> for AttachmentError {
+    fn from(e: tarpc::Error<AttachmentError>) -> AttachmentError {
+        use tarpc::Error::*;
+        match e {
+            App(ee) => ee,
+            _ => AttachmentError::InternalServerError,
+        }
+    }
+}
+
+impl From<tarpc::Error<SettingsError>> for SettingsError {
+    fn from(e: tarpc::Error<SettingsError>) -> SettingsError {
+        use tarpc::Error::*;
+        match e {
+            App(ee) => ee,
+            _ => SettingsError::InternalServerError,
+        }
+    }
+}
+
 impl From<AuthError> for ResponseError {
     fn from(e: AuthError) -> Self {
         ResponseError::AuthRequestError(e)
@@ -84,3 +196,27 @@ impl From<AdminError> for ResponseError {
         ResponseError::AdminRequestError(e)
     }
 }
+
+impl From<MessageError> for ResponseError {
+    fn from(e: MessageError) -> Self {
+        ResponseError::MessageRequestError(e)
+    }
+}
+
+impl From<NotificationError> for ResponseError {
+    fn from(e: NotificationError) -> Self {
+        ResponseError::NotificationRequestError(e)
+    }
+}
+
+impl From<AttachmentError> for ResponseError {
+    fn from(e: AttachmentError) -> Self {
+        ResponseError::AttachmentRequestError(e)
+    }
+}
+
+impl From<SettingsError> for ResponseError {
+    fn from(e: SettingsError) -> Self {
+        ResponseError::SettingsRequestError(e)
+    }
+}
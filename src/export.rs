@@ -0,0 +1,208 @@
+//! A self-contained archive of all the data of a single user
+//!
+//! The archive is built from the same payloads which are used in the
+//! responses of the services, so it can be read back with the same types.
+
+use chrono::naive::NaiveDateTime;
+use crate::content::responses::{CommentPayload, PrivateUserPayload, ThreadPayload};
+use crate::messages::responses::{ConversationPayload, MessagePayload};
+use crate::settings::responses::UserSettings;
+use crate::valid::ids::UserId;
+
+/// The version of the format of `DataExport`, which is bumped whenever the
+/// format changes
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DataExport {
+    pub version: u32,
+    pub generated: NaiveDateTime,
+    pub profile: PrivateUserPayload,
+    pub settings: UserSettings,
+    pub threads: Vec<ThreadPayload>,
+    pub comments: Vec<CommentPayload>,
+    pub conversations: Vec<ConversationExport>,
+}
+
+/// A conversation the user participates in together with all its messages
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ConversationExport {
+    pub conversation: ConversationPayload,
+    pub messages: Vec<MessagePayload>,
+}
+
+impl DataExport {
+    /// Create a new export of a user without any content
+    pub fn new(
+        generated: NaiveDateTime,
+        profile: PrivateUserPayload,
+        settings: UserSettings,
+    ) -> Self {
+        DataExport {
+            version: EXPORT_FORMAT_VERSION,
+            generated,
+            profile,
+            settings,
+            threads: Vec::new(),
+            comments: Vec::new(),
+            conversations: Vec::new(),
+        }
+    }
+
+    /// Replace a deleted user with the tombstone user everywhere in the
+    /// content of the export, e.g. in the conversations they took part in
+    pub fn anonymize(&mut self, deleted: UserId) {
+        for thread in &mut self.threads {
+            thread.anonymize(deleted);
+        }
+        for comment in &mut self.comments {
+            comment.anonymize(deleted);
+        }
+        for export in &mut self.conversations {
+            export.conversation.anonymize(deleted);
+            for message in &mut export.messages {
+                message.anonymize(deleted);
+            }
+        }
+    }
+
+    /// Serialize the export as a human readable JSON document
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::naive::NaiveDate;
+    use crate::auth::responses::Role;
+    use crate::content::responses::*;
+    use crate::valid::ids::*;
+    use crate::valid::ValidationError;
+    use std::convert::TryFrom;
+
+    fn field<T>(s: &str) -> T
+    where
+        T: TryFrom<String, Error = ValidationError>,
+    {
+        T::try_from(s.to_owned()).unwrap()
+    }
+
+    fn time() -> NaiveDateTime {
+        NaiveDate::from_ymd(2018, 10, 1).and_hms(12, 0, 0)
+    }
+
+    fn message(id: u32, user_id: u32) -> MessagePayload {
+        MessagePayload {
+            id: MessageId::from(id),
+            conversation_id: ConversationId::from(1),
+            user_id: UserId::from(user_id),
+            content: field("Hi \"you\" & <me>"),
+            timestamp: time(),
+        }
+    }
+
+    fn export() -> DataExport {
+        let profile = PrivateUserPayload {
+            profile: PublicUserPayload {
+                id: UserId::from(1),
+                username: field("john"),
+                display_name: Some(field("John \"JJ\" O'Neil")),
+                description: Some(field("Tom & Jerry <3")),
+                avatar: None,
+                signature: None,
+                location: Some(field("Bø & Co")),
+                website: Some(field("https://example.com/?a=1&b=2")),
+                role: Role::User,
+                joined: time(),
+                thread_count: Some(1),
+                comment_count: Some(1),
+            },
+            email: Some(field("o'neil@example.com")),
+            privacy: PrivacySettings::default(),
+        };
+        let mut export = DataExport::new(time(), profile, UserSettings::default());
+        export.threads.push(ThreadPayload {
+            id: ThreadId::from(1),
+            category_id: CategoryId::from(1),
+            user_id: UserId::from(1),
+            title: field("Tom & Jerry's <show>"),
+            description: field("A \"classic\""),
+            tags: vec![field("cartoons")],
+            attachments: Vec::new(),
+            timestamp: time(),
+            hidden: false,
+            state: ThreadState::default(),
+            poll: None,
+            bookmarked: false,
+            unread_count: 0,
+            first_unread_id: None,
+            upvotes: 1,
+            downvotes: 0,
+            reactions: Vec::new(),
+        });
+        export.comments.push(CommentPayload {
+            id: CommentId::from(1),
+            thread_id: ThreadId::from(1),
+            parent_id: None,
+            user_id: UserId::from(1),
+            content: field("Yes & no <b>"),
+            attachments: Vec::new(),
+            timestamp: time(),
+            hidden: false,
+            upvotes: 0,
+            downvotes: 0,
+            reactions: Vec::new(),
+        });
+        export.conversations.push(ConversationExport {
+            conversation: ConversationPayload {
+                id: ConversationId::from(1),
+                subject: field("Lunch & stuff"),
+                participants: vec![UserId::from(1), UserId::from(2)],
+                last_message: Some(message(2, 2)),
+                unread_count: 0,
+                timestamp: time(),
+            },
+            messages: vec![message(1, 1), message(2, 2)],
+        });
+        export
+    }
+
+    #[test]
+    fn survives_serialization() {
+        let export = export();
+        let json = export.to_json().unwrap();
+        let parsed: DataExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, export);
+    }
+
+    #[test]
+    fn anonymizes_deleted_users() {
+        let mut export = export();
+        export.anonymize(UserId::from(2));
+
+        let conversation = &export.conversations[0];
+        assert_eq!(
+            conversation.conversation.participants,
+            vec![UserId::from(1), UserId::DELETED]
+        );
+        assert_eq!(
+            conversation.conversation.last_message.as_ref().unwrap().user_id,
+            UserId::DELETED
+        );
+        assert_eq!(conversation.messages[0].user_id, UserId::from(1));
+        assert_eq!(conversation.messages[1].user_id, UserId::DELETED);
+        assert_eq!(export.threads[0].user_id, UserId::from(1));
+        assert_eq!(export.comments[0].user_id, UserId::from(1));
+
+        export.anonymize(UserId::from(1));
+        assert_eq!(export.threads[0].user_id, UserId::DELETED);
+        assert_eq!(export.comments[0].user_id, UserId::DELETED);
+        assert!(export.conversations[0]
+            .conversation
+            .participants
+            .iter()
+            .all(|id| *id == UserId::DELETED));
+    }
+}
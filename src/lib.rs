@@ -12,7 +12,9 @@ extern crate failure_derive;
 extern crate lazy_static;
 extern crate chrono;
 extern crate htmlescape;
+extern crate idna;
 extern crate regex;
+extern crate sha2;
 extern crate tarpc;
 
 #[cfg(test)]
@@ -22,8 +24,13 @@ extern crate proptest;
 #[macro_use]
 pub mod macros;
 pub mod admin;
+pub mod attachments;
 pub mod auth;
 pub mod content;
 pub mod error;
+pub mod export;
+pub mod messages;
+pub mod notifications;
 pub mod payloads;
+pub mod settings;
 pub mod valid;
@@ -31,6 +31,22 @@ macro_rules! impl_serialize {
     };
 }
 
+/// A convenience macro to implement serialize for a item which is stored
+/// exactly as it should be sent, without escaping it again
+#[macro_export]
+macro_rules! impl_serialize_raw {
+    ($ident:ident) => {
+        impl serde::Serialize for $ident {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_ref())
+            }
+        }
+    };
+}
+
 /// Generates relevant impls for ids
 #[macro_export]
 macro_rules! id_impls {
@@ -0,0 +1,82 @@
+//! The requests a user can make to the messaging-service
+
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum MessageRequest {
+    StartConversation(StartConversationPayload),
+    SendMessage(SendMessagePayload),
+    MarkRead(MarkReadPayload),
+    LeaveConversation(LeaveConversationPayload),
+
+    BlockUser(BlockUserPayload),
+    UnblockUser(BlockUserPayload),
+}
+
+// Conversations
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetConversationsPayload {
+    pub user_id: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct StartConversationPayload {
+    pub user_id: Option<UserId>,
+    pub participants: Vec<UserId>,
+    pub subject: Title,
+    pub content: MessageContent,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct LeaveConversationPayload {
+    pub id: ConversationId,
+    pub user_id: Option<UserId>,
+}
+
+// Messages
+
+/// Get the messages of a conversation, newest first
+///
+/// Only messages older than `before` are included if it is present.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetMessagesPayload {
+    pub id: ConversationId,
+    pub user_id: Option<UserId>,
+    pub before: Option<MessageId>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SendMessagePayload {
+    pub conversation_id: ConversationId,
+    pub user_id: Option<UserId>,
+    pub content: MessageContent,
+}
+
+/// Mark all messages up to and including `last_message_id` as read
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MarkReadPayload {
+    pub conversation_id: ConversationId,
+    pub user_id: Option<UserId>,
+    pub last_message_id: MessageId,
+}
+
+// Block list
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetBlockedUsersPayload {
+    pub user_id: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BlockUserPayload {
+    pub user_id: Option<UserId>,
+    pub blocked_id: UserId,
+}
@@ -0,0 +1,103 @@
+//! The responses a user will get on requests to the messaging-service
+
+use chrono::naive::NaiveDateTime;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+/// All the successful responses to a `MessageRequest`
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum MessageSuccess {
+    Conversation(ConversationPayload),
+    Conversations(ConversationsPayload),
+    Message(MessagePayload),
+    Messages(Vec<MessagePayload>),
+    MarkedRead,
+    LeftConversation,
+    UserBlocked,
+    UserUnblocked,
+    BlockedUsers(Vec<UserId>),
+}
+
+/// All the unsuccessful responses to a `MessageRequest`
+#[derive(Fail, Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub enum MessageError {
+    #[fail(display = "conversation was not found")]
+    MissingConversation,
+    #[fail(display = "user is not a participant of the conversation")]
+    NotParticipant,
+    #[fail(display = "conversation has no other participants")]
+    NoRecipients,
+    #[fail(display = "user {} has blocked you", user_id)]
+    Blocked { user_id: UserId },
+    #[fail(display = "internal server error")]
+    InternalServerError,
+}
+
+/// Vertify that `sender` can send messages to all of the `recipients`
+///
+/// Each recipient is given together with the users they have blocked. The
+/// first recipient which has blocked the sender is reported in the error.
+pub fn check_not_blocked<'a, I>(sender: UserId, recipients: I) -> Result<(), MessageError>
+where
+    I: IntoIterator<Item = (UserId, &'a [UserId])>,
+{
+    match recipients
+        .into_iter()
+        .find(|(_, blocked)| blocked.contains(&sender))
+    {
+        Some((user_id, _)) => Err(MessageError::Blocked { user_id }),
+        None => Ok(()),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct MessagePayload {
+    pub id: MessageId,
+    pub conversation_id: ConversationId,
+    pub user_id: UserId,
+    pub content: MessageContent,
+    pub timestamp: NaiveDateTime,
+}
+
+impl MessagePayload {
+    /// Replace the deleted user with the tombstone user, if they are the
+    /// author of the message
+    pub fn anonymize(&mut self, deleted: UserId) {
+        self.user_id.anonymize(deleted);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ConversationPayload {
+    pub id: ConversationId,
+    pub subject: Title,
+    pub participants: Vec<UserId>,
+    pub last_message: Option<MessagePayload>,
+    pub unread_count: u32,
+    pub timestamp: NaiveDateTime,
+}
+
+impl ConversationPayload {
+    /// Replace the deleted user with the tombstone user among the
+    /// participants and in the last message
+    pub fn anonymize(&mut self, deleted: UserId) {
+        for participant in &mut self.participants {
+            participant.anonymize(deleted);
+        }
+        if let Some(message) = &mut self.last_message {
+            message.anonymize(deleted);
+        }
+    }
+}
+
+/// All the conversations of a user together with the total unread count
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ConversationsPayload {
+    pub conversations: Vec<ConversationPayload>,
+    pub unread_count: u32,
+}
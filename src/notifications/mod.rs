@@ -0,0 +1,4 @@
+//! Notification requests from and responses to the web-client
+
+pub mod requests;
+pub mod responses;
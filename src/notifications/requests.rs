@@ -0,0 +1,65 @@
+//! The requests a user can make to the notification-service
+
+use crate::payloads::PagePayload;
+use crate::valid::ids::*;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum NotificationRequest {
+    Subscribe(SubscriptionPayload),
+    Unsubscribe(SubscriptionPayload),
+
+    MarkRead(MarkReadPayload),
+    MarkAllRead(MarkAllReadPayload),
+}
+
+// Subscriptions
+
+/// Something a user can subscribe to, to be notified of new content
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[serde(
+    tag = "type",
+    content = "id",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum SubscriptionTarget {
+    Thread(ThreadId),
+    Category(CategoryId),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetSubscriptionsPayload {
+    pub user_id: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SubscriptionPayload {
+    pub user_id: Option<UserId>,
+    pub target: SubscriptionTarget,
+}
+
+// Notifications
+
+/// Get a page of notifications, newest first
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetNotificationsPayload {
+    pub user_id: Option<UserId>,
+    pub unread_only: bool,
+    #[serde(flatten)]
+    pub page: PagePayload,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MarkReadPayload {
+    pub user_id: Option<UserId>,
+    pub ids: Vec<NotificationId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct MarkAllReadPayload {
+    pub user_id: Option<UserId>,
+}
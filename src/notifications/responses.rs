@@ -0,0 +1,85 @@
+//! The responses a user will get on requests to the notification-service
+
+use chrono::naive::NaiveDateTime;
+use crate::auth::responses::Role;
+use crate::notifications::requests::SubscriptionTarget;
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+
+/// All the successful responses to a `NotificationRequest`
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum NotificationSuccess {
+    Notifications(NotificationsPayload),
+    Subscriptions(Vec<SubscriptionTarget>),
+    Subscribed,
+    Unsubscribed,
+    MarkedRead,
+}
+
+/// All the unsuccessful responses to a `NotificationRequest`
+#[derive(Fail, Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub enum NotificationError {
+    #[fail(display = "notification was not found")]
+    MissingNotification,
+    #[fail(display = "subscription target was not found")]
+    MissingTarget,
+    #[fail(display = "user is already subscribed")]
+    AlreadySubscribed,
+    #[fail(display = "user is not subscribed")]
+    NotSubscribed,
+    #[fail(display = "internal server error")]
+    InternalServerError,
+}
+
+/// The event a user is notified about
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum Notification {
+    /// Someone replied to a comment of the user
+    ReplyToComment {
+        thread_id: ThreadId,
+        comment_id: CommentId,
+        parent_id: CommentId,
+        author_id: UserId,
+    },
+    /// Someone commented in a thread (or category) the user is subscribed to
+    ReplyInThread {
+        thread_id: ThreadId,
+        comment_id: CommentId,
+        author_id: UserId,
+    },
+    /// Someone mentioned the user in a thread or a comment
+    Mention {
+        target: ContentRef,
+        author_id: UserId,
+    },
+    /// A moderator acted on content created by the user
+    ModerationAction { target: ContentRef, reason: Reason },
+    /// The role of the user was changed by an admin
+    RoleChanged { role: Role },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct NotificationPayload {
+    pub id: NotificationId,
+    pub notification: Notification,
+    pub read: bool,
+    pub timestamp: NaiveDateTime,
+}
+
+/// A page of notifications together with the totals of the user
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct NotificationsPayload {
+    pub notifications: Vec<NotificationPayload>,
+    pub unread_count: u32,
+    pub total: u32,
+}
@@ -2,6 +2,7 @@
 
 use crate::valid::ids::UserId;
 use crate::valid::token::Token;
+use std::cmp;
 use std::ops::{Deref, DerefMut};
 
 /// A payload which must be present, but empty
@@ -59,6 +60,57 @@ pub struct EmptyPayloadStrict {}
 /// ```
 pub type EmptyPayload = Option<EmptyPayloadStrict>;
 
+/// Represents a request for a single page of a larger listing
+///
+/// Both fields can be omitted, in which case the first page with
+/// `DEFAULT_LIMIT` items is requested. This payload is intended to be used
+/// with `#[serde(flatten)]` in a surrounding type.
+///
+/// # Examples
+///
+/// ```
+/// # use datatypes::payloads::PagePayload;
+/// let page: PagePayload = serde_json::from_str(r#"{ "offset": 50 }"#).unwrap();
+/// assert_eq!(page.offset, 50);
+/// assert_eq!(page.limit(), PagePayload::DEFAULT_LIMIT);
+///
+/// let page: PagePayload = serde_json::from_str(r#"{ "limit": 1000 }"#).unwrap();
+/// assert_eq!(page.limit(), PagePayload::MAX_LIMIT);
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub struct PagePayload {
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default = "PagePayload::default_limit")]
+    pub limit: u32,
+}
+
+impl PagePayload {
+    /// The number of items in a page if no limit is given
+    pub const DEFAULT_LIMIT: u32 = 25;
+
+    /// The maximum number of items in a single page
+    pub const MAX_LIMIT: u32 = 100;
+
+    fn default_limit() -> u32 {
+        Self::DEFAULT_LIMIT
+    }
+
+    /// Get the number of items in the page, capped at `MAX_LIMIT`
+    pub fn limit(&self) -> u32 {
+        cmp::min(self.limit, Self::MAX_LIMIT)
+    }
+}
+
+impl Default for PagePayload {
+    fn default() -> Self {
+        PagePayload {
+            offset: 0,
+            limit: Self::DEFAULT_LIMIT,
+        }
+    }
+}
+
 /// Represents a payload that also contains a authorization token
 ///
 /// This payload is generic for both the inner type and the token type, this
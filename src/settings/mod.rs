@@ -0,0 +1,4 @@
+//! User settings requests from and responses to the web-client
+
+pub mod requests;
+pub mod responses;
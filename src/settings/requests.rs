@@ -0,0 +1,48 @@
+//! The requests a user can make to the settings-service
+
+use crate::settings::responses::{deserialize_or_default, NotificationChannels, SortOrder, Theme};
+use crate::valid::fields::*;
+use crate::valid::ids::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum SettingsRequest {
+    UpdateSettings(UpdateSettingsPayload),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct GetSettingsPayload {
+    pub user_id: Option<UserId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct UpdateSettingsPayload {
+    pub user_id: Option<UserId>,
+    pub settings: UserSettingsPatch,
+}
+
+/// A partial update of `UserSettings`
+///
+/// Only the settings which are present are changed. Unknown settings (e.g.
+/// from a newer client) are stored as they are, and an unknown setting with
+/// the value `null` is removed. Invalid values of known settings (e.g. a
+/// theme from a newer client) are ignored.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct UserSettingsPatch {
+    pub timezone: Option<Timezone>,
+    pub locale: Option<Locale>,
+    #[serde(default, deserialize_with = "deserialize_or_default")]
+    pub theme: Option<Theme>,
+    pub notifications: Option<NotificationChannels>,
+    pub comments_per_page: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_or_default")]
+    pub default_sort: Option<SortOrder>,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
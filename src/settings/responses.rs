@@ -0,0 +1,265 @@
+//! The responses a user will get on requests to the settings-service
+
+use crate::settings::requests::UserSettingsPatch;
+use crate::valid::fields::*;
+use crate::valid::ValidationError;
+use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The maximum number of unknown settings which are stored for a user
+pub const MAX_UNKNOWN_SETTINGS: usize = 50;
+
+/// The maximum size of the unknown settings of a user, as serialized JSON
+pub const MAX_UNKNOWN_SETTINGS_LEN: usize = 8 * 1024;
+
+/// All the successful responses to a `SettingsRequest`
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(
+    tag = "type",
+    content = "payload",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum SettingsSuccess {
+    Settings(UserSettings),
+}
+
+/// All the unsuccessful responses to a `SettingsRequest`
+#[derive(Fail, Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub enum SettingsError {
+    #[fail(display = "invalid settings")]
+    InvalidSettings,
+    #[fail(display = "internal server error")]
+    InternalServerError,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Theme {
+    Light,
+    Dark,
+    /// Follow the preference of the operating system
+    System,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::System
+    }
+}
+
+/// The order threads and comments are listed in
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SortOrder {
+    Newest,
+    Oldest,
+    /// Best first, see `content::ranking::wilson_score`
+    Top,
+    /// Hottest first, see `content::ranking::hot_rank`
+    Hot,
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        SortOrder::Oldest
+    }
+}
+
+/// Deserialize a setting which falls back to its default value if it is
+/// invalid, e.g. a theme which was added in a newer client
+///
+/// Intended to be used with `#[serde(deserialize_with = "...")]`
+pub fn deserialize_or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
+/// The channels a user wants to receive notifications through
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub struct NotificationChannels {
+    pub web: bool,
+    pub email: bool,
+}
+
+/// The preferences of a single user
+///
+/// Missing settings are given their default value when deserialized, and
+/// unknown settings are kept in `unknown` so that they are preserved when the
+/// settings are stored by a service which does not know about them.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(default)]
+pub struct UserSettings {
+    pub timezone: Timezone,
+    pub locale: Locale,
+    #[serde(deserialize_with = "deserialize_or_default")]
+    pub theme: Theme,
+    pub notifications: NotificationChannels,
+    pub comments_per_page: u32,
+    #[serde(deserialize_with = "deserialize_or_default")]
+    pub default_sort: SortOrder,
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+impl UserSettings {
+    /// The minimum number of comments which can be shown per page
+    pub const MIN_COMMENTS_PER_PAGE: u32 = 5;
+
+    /// The maximum number of comments which can be shown per page
+    pub const MAX_COMMENTS_PER_PAGE: u32 = 200;
+
+    /// Apply a partial update to the settings
+    ///
+    /// The settings are left unchanged if the update is invalid, e.g. if it
+    /// would store more than `MAX_UNKNOWN_SETTINGS` unknown settings or more
+    /// than `MAX_UNKNOWN_SETTINGS_LEN` bytes of them.
+    pub fn apply(&mut self, patch: UserSettingsPatch) -> Result<(), ValidationError> {
+        if let Some(n) = patch.comments_per_page {
+            if n < Self::MIN_COMMENTS_PER_PAGE || Self::MAX_COMMENTS_PER_PAGE < n {
+                return Err(ValidationError::InvalidCommentsPerPage);
+            }
+        }
+        let mut unknown = self.unknown.clone();
+        for (key, value) in patch.unknown {
+            if value.is_null() {
+                unknown.remove(&key);
+            } else {
+                unknown.insert(key, value);
+            }
+        }
+        check_unknown_settings(&unknown)?;
+
+        self.unknown = unknown;
+        if let Some(n) = patch.comments_per_page {
+            self.comments_per_page = n;
+        }
+        if let Some(timezone) = patch.timezone {
+            self.timezone = timezone;
+        }
+        if let Some(locale) = patch.locale {
+            self.locale = locale;
+        }
+        if let Some(theme) = patch.theme {
+            self.theme = theme;
+        }
+        if let Some(notifications) = patch.notifications {
+            self.notifications = notifications;
+        }
+        if let Some(default_sort) = patch.default_sort {
+            self.default_sort = default_sort;
+        }
+        Ok(())
+    }
+}
+
+/// Vertify that the unknown settings are within `MAX_UNKNOWN_SETTINGS` and
+/// `MAX_UNKNOWN_SETTINGS_LEN`
+pub fn check_unknown_settings(unknown: &BTreeMap<String, Value>) -> Result<(), ValidationError> {
+    let len = serde_json::to_vec(unknown).map(|v| v.len()).unwrap_or(usize::max_value());
+    if unknown.len() <= MAX_UNKNOWN_SETTINGS && len <= MAX_UNKNOWN_SETTINGS_LEN {
+        Ok(())
+    } else {
+        Err(ValidationError::TooManyUnknownSettings)
+    }
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        UserSettings {
+            timezone: Timezone::default(),
+            locale: Locale::default(),
+            theme: Theme::default(),
+            notifications: NotificationChannels {
+                web: true,
+                email: false,
+            },
+            comments_per_page: 25,
+            default_sort: SortOrder::default(),
+            unknown: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preserves_unknown_settings() {
+        let json = r#"{ "theme": "DARK", "font_size": 14 }"#;
+        let settings: UserSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.theme, Theme::Dark);
+        assert_eq!(settings.comments_per_page, 25);
+        assert_eq!(settings.unknown.get("font_size"), Some(&Value::from(14)));
+
+        let json = serde_json::to_value(&settings).unwrap();
+        assert_eq!(json["font_size"], Value::from(14));
+    }
+
+    #[test]
+    fn applies_partial_updates() {
+        let mut settings = UserSettings::default();
+        settings.unknown.insert("font_size".to_owned(), Value::from(14));
+
+        let json = r#"{ "comments_per_page": 50, "font_size": null, "compact": true }"#;
+        let patch: UserSettingsPatch = serde_json::from_str(json).unwrap();
+        settings.apply(patch).unwrap();
+        assert_eq!(settings.comments_per_page, 50);
+        assert_eq!(settings.theme, Theme::System);
+        assert_eq!(settings.unknown.get("font_size"), None);
+        assert_eq!(settings.unknown.get("compact"), Some(&Value::from(true)));
+
+        let patch = UserSettingsPatch {
+            comments_per_page: Some(1000),
+            ..UserSettingsPatch::default()
+        };
+        assert!(settings.apply(patch).is_err());
+        assert_eq!(settings.comments_per_page, 50);
+    }
+
+    #[test]
+    fn unknown_values_fall_back_to_default() {
+        let json = r#"{ "theme": "SEPIA", "default_sort": "RANDOM", "comments_per_page": 50 }"#;
+        let settings: UserSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.theme, Theme::System);
+        assert_eq!(settings.default_sort, SortOrder::Oldest);
+        assert_eq!(settings.comments_per_page, 50);
+
+        let json = r#"{ "theme": "SEPIA", "locale": "nb-NO" }"#;
+        let patch: UserSettingsPatch = serde_json::from_str(json).unwrap();
+        assert_eq!(patch.theme, None);
+        assert!(patch.locale.is_some());
+    }
+
+    #[test]
+    fn limits_unknown_settings() {
+        let mut settings = UserSettings::default();
+        let mut patch = UserSettingsPatch::default();
+        for i in 0..=MAX_UNKNOWN_SETTINGS {
+            patch.unknown.insert(format!("setting_{}", i), Value::from(i));
+        }
+        patch.comments_per_page = Some(50);
+        assert_eq!(
+            settings.apply(patch),
+            Err(ValidationError::TooManyUnknownSettings)
+        );
+        assert_eq!(settings, UserSettings::default());
+
+        let mut patch = UserSettingsPatch::default();
+        let large = "x".repeat(MAX_UNKNOWN_SETTINGS_LEN);
+        patch.unknown.insert("large".to_owned(), Value::from(large));
+        assert_eq!(
+            settings.apply(patch),
+            Err(ValidationError::TooManyUnknownSettings)
+        );
+
+        let mut patch = UserSettingsPatch::default();
+        patch.unknown.insert("font_size".to_owned(), Value::from(14));
+        assert_eq!(settings.apply(patch), Ok(()));
+    }
+}
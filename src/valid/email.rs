@@ -0,0 +1,209 @@
+//! Parsing of email addresses following RFC 5321, with support for
+//! internationalized domain names (and UTF-8 local parts as in RFC 6531)
+
+use super::ValidationError;
+use std::collections::HashSet;
+
+/// The maximum length of the local part (RFC 5321 section 4.5.3.1.1)
+const MAX_LOCAL_LEN: usize = 64;
+
+/// The maximum length of a domain (RFC 5321 section 4.5.3.1.2)
+const MAX_DOMAIN_LEN: usize = 255;
+
+/// The maximum length of a label in a domain
+const MAX_LABEL_LEN: usize = 63;
+
+/// The maximum length of a address, limited by the length of a path (RFC
+/// 5321 section 4.5.3.1.3) without the angle brackets
+const MAX_ADDRESS_LEN: usize = 254;
+
+/// The bundled list of disposable email domains
+const DISPOSABLE_DOMAINS: &str = include_str!("disposable_domains.txt");
+
+/// A email address split into its parts
+#[derive(PartialEq, Debug)]
+pub(crate) struct Address<'a> {
+    /// The local part as written, including quotes if it is quoted
+    pub local: &'a str,
+    /// The domain as written
+    pub domain: &'a str,
+    /// The domain in ASCII (punycode) form, lowercased
+    pub ascii_domain: String,
+}
+
+/// Parse and validate a email address
+pub(crate) fn parse(s: &str) -> Result<Address<'_>, ValidationError> {
+    let at = if s.starts_with('"') {
+        quoted_string_len(s).ok_or(ValidationError::InvalidEmail)?
+    } else {
+        s.find('@').ok_or(ValidationError::InvalidEmail)?
+    };
+    let (local, domain) = (&s[..at], &s[at..]);
+    if !domain.starts_with('@') {
+        return Err(ValidationError::InvalidEmail);
+    }
+    let domain = &domain[1..];
+
+    let valid_local =
+        local.len() <= MAX_LOCAL_LEN && (local.starts_with('"') || is_dot_string(local));
+    if !valid_local || s.len() > MAX_ADDRESS_LEN {
+        return Err(ValidationError::InvalidEmail);
+    }
+    let ascii_domain = parse_domain(domain).ok_or(ValidationError::InvalidEmail)?;
+    Ok(Address {
+        local,
+        domain,
+        ascii_domain,
+    })
+}
+
+/// Check if `c` is allowed unquoted in the local part, `atext` in RFC 5322
+/// extended with non-ASCII characters by RFC 6531
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || "!#$%&'*+-/=?^_`{|}~".contains(c)
+        || (!c.is_ascii() && !c.is_control())
+}
+
+/// Check if `s` is a `Dot-string`, one or more atoms separated by single dots
+fn is_dot_string(s: &str) -> bool {
+    s.split('.').all(|atom| !atom.is_empty() && atom.chars().all(is_atext))
+}
+
+/// The length of the `Quoted-string` at the start of `s`, including the
+/// quotes
+fn quoted_string_len(s: &str) -> Option<usize> {
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if i > 1 => return Some(i + 1),
+            '\\' => match chars.next() {
+                Some((_, ' '..='~')) => {}
+                _ => return None,
+            },
+            ' '..='~' | '\u{80}'..='\u{10FFFF}' if c != '"' => {}
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Validate a domain or address literal, returning it in lowercase ASCII form
+fn parse_domain(domain: &str) -> Option<String> {
+    if domain.starts_with('[') && domain.ends_with(']') {
+        let literal = &domain[1..domain.len() - 1];
+        let is_ipv6 = literal
+            .get(..5)
+            .map_or(false, |p| p.eq_ignore_ascii_case("IPv6:"));
+        let valid = if is_ipv6 {
+            literal[5..].parse::<std::net::Ipv6Addr>().is_ok()
+        } else {
+            literal.parse::<std::net::Ipv4Addr>().is_ok()
+        };
+        return if valid {
+            Some(domain.to_ascii_lowercase())
+        } else {
+            None
+        };
+    }
+    let ascii = idna::domain_to_ascii(domain).ok()?;
+    let labels: Vec<&str> = ascii.split('.').collect();
+    let valid_labels = labels.iter().all(|label| {
+        !label.is_empty()
+            && label.len() <= MAX_LABEL_LEN
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    let tld = labels[labels.len() - 1];
+    let valid_tld = !tld.chars().all(|c| c.is_ascii_digit());
+    if labels.len() >= 2 && valid_labels && valid_tld && ascii.len() <= MAX_DOMAIN_LEN {
+        Some(ascii)
+    } else {
+        None
+    }
+}
+
+/// Check if a (lowercase ASCII) domain or one of its parent domains is in the
+/// bundled list of disposable email domains
+pub(crate) fn is_disposable_domain(ascii_domain: &str) -> bool {
+    lazy_static! {
+        static ref DOMAINS: HashSet<&'static str> = DISPOSABLE_DOMAINS
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect();
+    }
+    ascii_domain
+        .match_indices('.')
+        .map(|(i, _)| &ascii_domain[i + 1..])
+        .chain(Some(ascii_domain))
+        .any(|d| DOMAINS.contains(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_parts() {
+        let address = parse("John.Doe@Mail.Example.co.uk").unwrap();
+        assert_eq!(address.local, "John.Doe");
+        assert_eq!(address.domain, "Mail.Example.co.uk");
+        assert_eq!(address.ascii_domain, "mail.example.co.uk");
+
+        let address = parse(r#""john@home \"doe\""@example.com"#).unwrap();
+        assert_eq!(address.local, r#""john@home \"doe\"""#);
+        assert_eq!(address.domain, "example.com");
+    }
+
+    #[test]
+    fn supports_internationalized_domains() {
+        let address = parse("post@bücher.de").unwrap();
+        assert_eq!(address.ascii_domain, "xn--bcher-kva.de");
+        assert!(parse("用户@例子.广告").is_ok());
+    }
+
+    #[test]
+    fn supports_address_literals() {
+        assert!(parse("john@[127.0.0.1]").is_ok());
+        assert!(parse("john@[IPv6:::1]").is_ok());
+        assert!(parse("john@[::1]").is_err());
+        assert!(parse("john@[300.0.0.1]").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_addresses() {
+        let invalid = vec![
+            "",
+            "@example.com",
+            "john@",
+            "john",
+            "john@localhost",
+            "john@example.123",
+            "john..doe@example.com",
+            ".john@example.com",
+            "john.@example.com",
+            "john doe@example.com",
+            "\"john\"doe@example.com",
+            "\"unterminated@example.com",
+            "john@-example.com",
+            "john@example-.com",
+            "john@exa_mple.com",
+            "john@example..com",
+        ];
+        for s in invalid {
+            assert!(parse(s).is_err(), "expected '{}' to be invalid", s);
+        }
+        assert!(parse(&format!("{}@example.com", "a".repeat(65))).is_err());
+        assert!(parse(&format!("john@{}.com", "a".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn detects_disposable_domains() {
+        assert!(is_disposable_domain("mailinator.com"));
+        assert!(is_disposable_domain("eu.mailinator.com"));
+        assert!(!is_disposable_domain("notmailinator.com"));
+        assert!(!is_disposable_domain("example.com"));
+    }
+}
@@ -1,19 +1,33 @@
 //! Validated datafields
+//!
+//! Free-text fields (e.g. `Title` and `CommentContent`) hold the text exactly
+//! as it was typed and are serialized as is, so they survive any number of
+//! round trips. They are HTML escaped once when they are displayed (their
+//! `Display` implementation), and never unescaped.
 
 // TODO add tests which vertifies the `TryFrom` implementations
 
+use super::email;
 use super::ValidationError;
 use htmlescape::encode_minimal;
 use rocket::http::RawStr;
 use rocket::request::FromFormValue;
+use serde::de::{Deserialize, Deserializer};
 use std::convert::TryFrom;
 use std::fmt::{self, Display};
+use std::marker::PhantomData;
 
-use super::{EMAIL_REGEX, PASSWORD_REGEX, SEARCH_QUERY_REGEX, USERNAME_REGEX};
+use super::{
+    EMOJI_SHORTCODE_REGEX, INVITE_CODE_REGEX, LOCALE_REGEX, PASSWORD_REGEX, SEARCH_QUERY_REGEX,
+    TAG_NAME_REGEX, TAG_PREFIX_REGEX, TIMEZONE_REGEX, USERNAME_REGEX, WEBSITE_REGEX,
+};
 use regex::Regex;
 
 /// A valid (well formatted) username
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+///
+/// Usernames are compared by exact spelling, use `canonical` to compare them
+/// the way users see them (e.g. to enforce uniqueness).
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Hash)]
 pub struct Username(String);
 
 impl TryFrom<String> for Username {
@@ -41,6 +55,125 @@ impl Display for Username {
     }
 }
 
+/// Usernames which cannot be registered, as they could be used to
+/// impersonate staff or the system
+///
+/// Names are matched by their canonical form, so e.g. `Adm1n` is reserved as
+/// well.
+pub const RESERVED_USERNAMES: &[&str] = &[
+    "admin",
+    "moderator",
+    "mods",
+    "staff",
+    "support",
+    "system",
+    "root",
+    "official",
+    "security",
+    "deleted",
+    "anonymous",
+    "null",
+    "undefined",
+];
+
+impl Username {
+    /// The key which usernames are compared by to decide if they are the
+    /// same name
+    pub fn canonical(&self) -> UsernameKey {
+        UsernameKey::new(&self.0)
+    }
+
+    /// Check if two usernames are (or look) the same
+    pub fn is_confusable_with(&self, other: &Username) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    /// Check if the username is reserved and cannot be registered
+    pub fn is_reserved(&self) -> bool {
+        let key = self.canonical();
+        RESERVED_USERNAMES.iter().any(|r| UsernameKey::new(r) == key)
+    }
+}
+
+/// The canonical form of a username, which is case insensitive and maps
+/// characters that look alike (e.g. `0` and `o`, `1`, `i` and `l`, `rn` and
+/// `m`) to the same form and ignores separators
+///
+/// Two usernames with the same key are considered the same name, so the key
+/// should be used for uniqueness constraints and lookups.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Hash)]
+pub struct UsernameKey(String);
+
+impl UsernameKey {
+    fn new(s: &str) -> Self {
+        let skeleton: String = s
+            .chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .map(|c| match c.to_ascii_lowercase() {
+                '0' => 'o',
+                '1' | 'i' => 'l',
+                '5' => 's',
+                '8' => 'b',
+                c => c,
+            })
+            .collect();
+        UsernameKey(skeleton.replace("rn", "m").replace("vv", "w"))
+    }
+}
+
+impl_serialize!(UsernameKey);
+impl_deref_and_as_ref!(UsernameKey => str);
+impl_into_inner!(UsernameKey => String);
+
+impl Display for UsernameKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Deserialize a username for a new account, which cannot be reserved
+///
+/// Intended to be used with `#[serde(deserialize_with = "...")]`
+pub fn deserialize_new_username<'de, D>(deserializer: D) -> Result<Username, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let username = Username::deserialize(deserializer)?;
+    if username.is_reserved() {
+        Err(serde::de::Error::custom(ValidationError::ReservedUsername))
+    } else {
+        Ok(username)
+    }
+}
+
+/// A valid (well formatted) display name, which is shown instead of the
+/// username on a profile
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct DisplayName(String);
+
+impl TryFrom<String> for DisplayName {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let s = s.trim();
+        if !s.is_empty() && s.chars().count() <= 32 && !s.chars().any(|c| c.is_control()) {
+            Ok(DisplayName(s.to_owned()))
+        } else {
+            Err(ValidationError::InvalidDisplayName)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(DisplayName);
+impl_serialize_raw!(DisplayName);
+impl_deref_and_as_ref!(DisplayName => str);
+impl_into_inner!(DisplayName => String);
+
+impl Display for DisplayName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode_minimal(&self.0))
+    }
+}
+
 /// A valid (well formatted) plaintext password
 ///
 /// NB This type does not implement `Debug` for the simple reason that a
@@ -80,7 +213,7 @@ impl TryFrom<String> for Title {
     type Error = ValidationError;
     fn try_from(s: String) -> Result<Self, Self::Error> {
         if 4 < s.len() && s.len() < 80 {
-            Ok(Title(htmlescape::encode_minimal(&s)))
+            Ok(Title(s))
         } else {
             Err(ValidationError::InvalidTitle)
         }
@@ -88,13 +221,13 @@ impl TryFrom<String> for Title {
 }
 
 impl_deserialize_with_try_from!(Title);
-impl_serialize!(Title);
+impl_serialize_raw!(Title);
 impl_deref_and_as_ref!(Title => str);
 impl_into_inner!(Title => String);
 
 impl Display for Title {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", encode_minimal(&self.0))
     }
 }
 
@@ -106,7 +239,7 @@ impl TryFrom<String> for Description {
     type Error = ValidationError;
     fn try_from(s: String) -> Result<Self, Self::Error> {
         if s.len() < 255 {
-            Ok(Description(htmlescape::encode_minimal(&s)))
+            Ok(Description(s))
         } else {
             Err(ValidationError::InvalidDescription)
         }
@@ -114,13 +247,13 @@ impl TryFrom<String> for Description {
 }
 
 impl_deserialize_with_try_from!(Description);
-impl_serialize!(Description);
+impl_serialize_raw!(Description);
 impl_deref_and_as_ref!(Description => str);
 impl_into_inner!(Description => String);
 
 impl Display for Description {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", encode_minimal(&self.0))
     }
 }
 
@@ -132,7 +265,7 @@ impl TryFrom<String> for CommentContent {
     type Error = ValidationError;
     fn try_from(s: String) -> Result<Self, Self::Error> {
         if s.len() > 4 && s.len() < 80 {
-            Ok(CommentContent(htmlescape::encode_minimal(&s)))
+            Ok(CommentContent(s))
         } else {
             Err(ValidationError::InvalidCommentContent)
         }
@@ -140,36 +273,141 @@ impl TryFrom<String> for CommentContent {
 }
 
 impl_deserialize_with_try_from!(CommentContent);
-impl_serialize!(CommentContent);
+impl_serialize_raw!(CommentContent);
 impl_deref_and_as_ref!(CommentContent => str);
 impl_into_inner!(CommentContent => String);
 
 impl Display for CommentContent {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", encode_minimal(&self.0))
     }
 }
 
-/// A valid (well formatted) email
+/// A valid (well formatted) private message
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
-pub struct Email(String);
+pub struct MessageContent(String);
 
-impl TryFrom<String> for Email {
+impl TryFrom<String> for MessageContent {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if !s.trim().is_empty() && s.len() < 2000 {
+            Ok(MessageContent(s))
+        } else {
+            Err(ValidationError::InvalidMessageContent)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(MessageContent);
+impl_serialize_raw!(MessageContent);
+impl_deref_and_as_ref!(MessageContent => str);
+impl_into_inner!(MessageContent => String);
+
+impl Display for MessageContent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode_minimal(&self.0))
+    }
+}
+
+/// A valid (well formatted) reason for a moderation action
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Reason(String);
+
+impl TryFrom<String> for Reason {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if !s.trim().is_empty() && s.len() < 255 {
+            Ok(Reason(s))
+        } else {
+            Err(ValidationError::InvalidReason)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(Reason);
+impl_serialize_raw!(Reason);
+impl_deref_and_as_ref!(Reason => str);
+impl_into_inner!(Reason => String);
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode_minimal(&self.0))
+    }
+}
+
+/// A valid (well formatted) location of a user
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Location(String);
+
+impl TryFrom<String> for Location {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if !s.trim().is_empty() && s.len() < 64 {
+            Ok(Location(s.trim().to_owned()))
+        } else {
+            Err(ValidationError::InvalidLocation)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(Location);
+impl_serialize_raw!(Location);
+impl_deref_and_as_ref!(Location => str);
+impl_into_inner!(Location => String);
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode_minimal(&self.0))
+    }
+}
+
+/// A valid (well formatted) http or https url of a website
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Website(String);
+
+impl TryFrom<String> for Website {
     type Error = ValidationError;
     fn try_from(s: String) -> Result<Self, Self::Error> {
         lazy_static! {
-            static ref RE: Regex = EMAIL_REGEX.parse().expect("invalid email regex");
+            static ref RE: Regex = WEBSITE_REGEX.parse().expect("invalid website regex");
         }
-        if RE.is_match(&s) {
-            Ok(Email(s))
+        if s.len() <= 200 && RE.is_match(&s) {
+            Ok(Website(s))
         } else {
-            Err(ValidationError::InvalidEmail)
+            Err(ValidationError::InvalidWebsite)
         }
     }
 }
 
+impl_deserialize_with_try_from!(Website);
+impl_serialize_raw!(Website);
+impl_deref_and_as_ref!(Website => str);
+impl_into_inner!(Website => String);
+
+impl Display for Website {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A valid (well formatted) email, as written by the user
+///
+/// Emails are compared by exact spelling, use `normalized` to compare them
+/// the way mail servers do (e.g. to enforce uniqueness).
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Email(String);
+
+impl TryFrom<String> for Email {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let s = s.trim();
+        email::parse(s)?;
+        Ok(Email(s.to_owned()))
+    }
+}
+
 impl_deserialize_with_try_from!(Email);
-impl_serialize!(Email);
+impl_serialize_raw!(Email);
 impl_deref_and_as_ref!(Email => str);
 impl_into_inner!(Email => String);
 
@@ -179,6 +417,106 @@ impl Display for Email {
     }
 }
 
+impl Email {
+    /// The local part of the address, e.g. `john` in `john@example.com`
+    pub fn local_part(&self) -> &str {
+        &self.0[..self.0.len() - self.domain().len() - 1]
+    }
+
+    /// The domain part of the address as written, e.g. `example.com`
+    pub fn domain(&self) -> &str {
+        self.0.rsplit('@').next().unwrap_or("")
+    }
+
+    /// The domain part of the address in lowercase ASCII (punycode) form, e.g.
+    /// `xn--bcher-kva.de` for `bücher.de`
+    pub fn ascii_domain(&self) -> String {
+        email::parse(&self.0)
+            .map(|address| address.ascii_domain)
+            .unwrap_or_default()
+    }
+
+    /// The normalized form of the address, which is the same for addresses
+    /// which are delivered to the same mailbox in practice (e.g.
+    /// `John@X.com` and `john@x.com`)
+    ///
+    /// The local part is case sensitive according to RFC 5321, but no major
+    /// mail provider treats it that way, so it is lowercased as well.
+    pub fn normalized(&self) -> String {
+        format!("{}@{}", self.local_part().to_lowercase(), self.ascii_domain())
+    }
+
+    /// Check if the address belongs to a known disposable email provider
+    pub fn is_disposable(&self) -> bool {
+        email::is_disposable_domain(&self.ascii_domain())
+    }
+}
+
+/// A valid (well formatted) invite code, which is required to register when
+/// the forum is invite only
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Hash)]
+pub struct InviteCode(String);
+
+impl TryFrom<String> for InviteCode {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref RE: Regex = INVITE_CODE_REGEX.parse().expect("invalid invite code regex");
+        }
+        let s = s.trim();
+        if RE.is_match(s) {
+            Ok(InviteCode(s.to_owned()))
+        } else {
+            Err(ValidationError::InvalidInviteCode)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(InviteCode);
+impl_serialize!(InviteCode);
+impl_deref_and_as_ref!(InviteCode => str);
+impl_into_inner!(InviteCode => String);
+
+impl Display for InviteCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A valid (well formatted) name of an uploaded file
+///
+/// The name cannot contain path separators or control characters, so it is
+/// safe to use as the last component of a path.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct FileName(String);
+
+impl TryFrom<String> for FileName {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if !s.trim().is_empty()
+            && s.len() <= 255
+            && s != "."
+            && s != ".."
+            && !s.chars().any(|c| c == '/' || c == '\\' || c.is_control())
+        {
+            Ok(FileName(s))
+        } else {
+            Err(ValidationError::InvalidFileName)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(FileName);
+impl_serialize_raw!(FileName);
+impl_deref_and_as_ref!(FileName => str);
+impl_into_inner!(FileName => String);
+
+impl Display for FileName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode_minimal(&self.0))
+    }
+}
+
 /// A valid (well formatted) search query string
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
 pub struct QueryStr(String);
@@ -216,6 +554,353 @@ impl<'a> FromFormValue<'a> for QueryStr {
     }
 }
 
+/// A valid (well formatted) emoji used as a reaction
+///
+/// Either a shortcode like `:thumbsup:` or a short sequence of non-ascii
+/// characters (to allow modifiers and zero-width-joiner sequences).
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Hash)]
+pub struct Emoji(String);
+
+impl TryFrom<String> for Emoji {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref RE: Regex = EMOJI_SHORTCODE_REGEX
+                .parse()
+                .expect("invalid emoji shortcode regex");
+        }
+        let len = s.chars().count();
+        let is_unicode = 0 < len
+            && len <= 10
+            && s
+                .chars()
+                .all(|c| !c.is_ascii() && !c.is_whitespace() && !c.is_control());
+        if RE.is_match(&s) || is_unicode {
+            Ok(Emoji(s))
+        } else {
+            Err(ValidationError::InvalidEmoji)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(Emoji);
+impl_serialize!(Emoji);
+impl_deref_and_as_ref!(Emoji => str);
+impl_into_inner!(Emoji => String);
+
+impl Display for Emoji {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The maximum number of tags a single thread can have
+pub const MAX_TAGS_PER_THREAD: usize = 5;
+
+/// A valid (well formatted) tag name
+///
+/// A tag name is a lowercase slug (e.g. `rust-lang`) between 2 and 24
+/// characters. Uppercase input is converted to lowercase.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Hash)]
+pub struct TagName(String);
+
+impl TryFrom<String> for TagName {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref RE: Regex = TAG_NAME_REGEX.parse().expect("invalid tag name regex");
+        }
+        let s = s.trim().to_lowercase();
+        if 2 <= s.len() && s.len() <= 24 && RE.is_match(&s) {
+            Ok(TagName(s))
+        } else {
+            Err(ValidationError::InvalidTag)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(TagName);
+impl_serialize!(TagName);
+impl_deref_and_as_ref!(TagName => str);
+impl_into_inner!(TagName => String);
+
+impl Display for TagName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A valid (well formatted) start of a tag name, used to autocomplete tags
+///
+/// Follows the same rules as `TagName`, except that it can be a single
+/// character and may end with a hyphen (e.g. `rust-`).
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TagPrefix(String);
+
+impl TryFrom<String> for TagPrefix {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref RE: Regex = TAG_PREFIX_REGEX.parse().expect("invalid tag prefix regex");
+        }
+        let s = s.trim().to_lowercase();
+        if s.len() <= 24 && RE.is_match(&s) {
+            Ok(TagPrefix(s))
+        } else {
+            Err(ValidationError::InvalidTagPrefix)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(TagPrefix);
+impl_serialize!(TagPrefix);
+impl_deref_and_as_ref!(TagPrefix => str);
+impl_into_inner!(TagPrefix => String);
+
+impl Display for TagPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Vertify that a list of tags has no duplicates and does not exceed
+/// `MAX_TAGS_PER_THREAD`
+pub fn validate_tags(tags: &[TagName]) -> Result<(), ValidationError> {
+    let is_unique = tags
+        .iter()
+        .enumerate()
+        .all(|(i, tag)| !tags[..i].contains(tag));
+    if tags.len() <= MAX_TAGS_PER_THREAD && is_unique {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidTagList)
+    }
+}
+
+/// Deserialize a list of tags which is validated with `validate_tags`
+///
+/// Intended to be used with `#[serde(deserialize_with = "...")]`
+pub fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<TagName>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tags = Vec::<TagName>::deserialize(deserializer)?;
+    validate_tags(&tags).map_err(serde::de::Error::custom)?;
+    Ok(tags)
+}
+
+/// Deserialize an optional list of tags which is validated with
+/// `validate_tags`
+///
+/// Intended to be used with `#[serde(deserialize_with = "...")]`
+pub fn deserialize_optional_tags<'de, D>(deserializer: D) -> Result<Option<Vec<TagName>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tags = Option::<Vec<TagName>>::deserialize(deserializer)?;
+    if let Some(ref tags) = tags {
+        validate_tags(tags).map_err(serde::de::Error::custom)?;
+    }
+    Ok(tags)
+}
+
+/// A valid (well formatted) timezone
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Timezone(String);
+
+impl TryFrom<String> for Timezone {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref RE: Regex = TIMEZONE_REGEX.parse().expect("invalid timezone regex");
+        }
+        if s.len() <= 64 && RE.is_match(&s) {
+            Ok(Timezone(s))
+        } else {
+            Err(ValidationError::InvalidTimezone)
+        }
+    }
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Timezone("UTC".to_owned())
+    }
+}
+
+impl_deserialize_with_try_from!(Timezone);
+impl_serialize!(Timezone);
+impl_deref_and_as_ref!(Timezone => str);
+impl_into_inner!(Timezone => String);
+
+impl Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A valid (well formatted) locale
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Locale(String);
+
+impl TryFrom<String> for Locale {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        lazy_static! {
+            static ref RE: Regex = LOCALE_REGEX.parse().expect("invalid locale regex");
+        }
+        if RE.is_match(&s) {
+            Ok(Locale(s))
+        } else {
+            Err(ValidationError::InvalidLocale)
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale("en".to_owned())
+    }
+}
+
+impl_deserialize_with_try_from!(Locale);
+impl_serialize!(Locale);
+impl_deref_and_as_ref!(Locale => str);
+impl_into_inner!(Locale => String);
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The maximum length of the text of a draft
+pub const MAX_DRAFT_LEN: usize = 10_000;
+
+/// Text which is meant to become a validated field (e.g. a `Title`), but
+/// which is not yet complete
+///
+/// The text is only limited in length, the validation of the complete type
+/// is deferred until `complete` is called (e.g. when a draft is published).
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct Incomplete<T> {
+    text: String,
+    complete: PhantomData<T>,
+}
+
+impl<T> TryFrom<String> for Incomplete<T> {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if s.len() <= MAX_DRAFT_LEN {
+            Ok(Incomplete {
+                text: s,
+                complete: PhantomData,
+            })
+        } else {
+            Err(ValidationError::InvalidDraft)
+        }
+    }
+}
+
+impl<T> Incomplete<T>
+where
+    T: TryFrom<String, Error = ValidationError>,
+{
+    /// Validate the text as the complete type
+    pub fn complete(self) -> Result<T, ValidationError> {
+        T::try_from(self.text)
+    }
+}
+
+impl<'de, T> serde::de::Deserialize<'de> for Incomplete<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Incomplete::try_from(s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<T> serde::Serialize for Incomplete<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<T> std::ops::Deref for Incomplete<T> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<T> AsRef<str> for Incomplete<T> {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+/// The maximum number of options a single poll can have
+pub const MAX_POLL_OPTIONS: usize = 10;
+
+/// A valid (well formatted) option of a poll
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
+pub struct PollOption(String);
+
+impl TryFrom<String> for PollOption {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if !s.trim().is_empty() && s.len() < 80 {
+            Ok(PollOption(s.trim().to_owned()))
+        } else {
+            Err(ValidationError::InvalidPollOption)
+        }
+    }
+}
+
+impl_deserialize_with_try_from!(PollOption);
+impl_serialize_raw!(PollOption);
+impl_deref_and_as_ref!(PollOption => str);
+impl_into_inner!(PollOption => String);
+
+impl Display for PollOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode_minimal(&self.0))
+    }
+}
+
+/// Vertify that a poll has between 2 and `MAX_POLL_OPTIONS` options and that
+/// no options are duplicates (ignoring case)
+pub fn validate_poll_options(options: &[PollOption]) -> Result<(), ValidationError> {
+    let lowercase: Vec<_> = options.iter().map(|o| o.to_lowercase()).collect();
+    let is_unique = lowercase
+        .iter()
+        .enumerate()
+        .all(|(i, option)| !lowercase[..i].contains(option));
+    if 2 <= options.len() && options.len() <= MAX_POLL_OPTIONS && is_unique {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidPollOptionList)
+    }
+}
+
+/// Deserialize the options of a poll which are validated with
+/// `validate_poll_options`
+///
+/// Intended to be used with `#[serde(deserialize_with = "...")]`
+pub fn deserialize_poll_options<'de, D>(deserializer: D) -> Result<Vec<PollOption>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let options = Vec::<PollOption>::deserialize(deserializer)?;
+    validate_poll_options(&options).map_err(serde::de::Error::custom)?;
+    Ok(options)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,7 +912,7 @@ mod tests {
                 for (s, expt) in $strs.into_iter() {
                     let res = $cons::try_from(String::from(s))
                         .expect(&format!("invalid {}", stringify!($cons)));
-                    assert_eq!(&*res, expt, "expected '{}' to be equal to {}", s, expt);
+                    assert_eq!(res.to_string(), expt, "expected '{}' to be equal to {}", s, expt);
                 }
             }
         };
@@ -261,12 +946,25 @@ mod tests {
     }
 
     doesnt_crash!(username_doesnt_crash, Username);
+    doesnt_crash!(display_name_doesnt_crash, DisplayName);
     doesnt_crash!(plain_password_doesnt_crash, PlainPassword);
     doesnt_crash!(title_doesnt_crash, Title);
     doesnt_crash!(description_doesnt_crash, Description);
     doesnt_crash!(comment_content_doesnt_crash, CommentContent);
+    doesnt_crash!(message_content_doesnt_crash, MessageContent);
+    doesnt_crash!(reason_doesnt_crash, Reason);
+    doesnt_crash!(location_doesnt_crash, Location);
+    doesnt_crash!(website_doesnt_crash, Website);
+    doesnt_crash!(timezone_doesnt_crash, Timezone);
+    doesnt_crash!(locale_doesnt_crash, Locale);
     doesnt_crash!(email_doesnt_crash, Email);
+    doesnt_crash!(invite_code_doesnt_crash, InviteCode);
     doesnt_crash!(query_str_doesnt_crash, QueryStr);
+    doesnt_crash!(file_name_doesnt_crash, FileName);
+    doesnt_crash!(poll_option_doesnt_crash, PollOption);
+    doesnt_crash!(emoji_doesnt_crash, Emoji);
+    doesnt_crash!(tag_name_doesnt_crash, TagName);
+    doesnt_crash!(tag_prefix_doesnt_crash, TagPrefix);
 
     test_input!(valid_usernames, Username, vec!["john", "irene"], true);
     test_input!(
@@ -275,20 +973,117 @@ mod tests {
         vec!["john", "irene", "a search with spaces"],
         true
     );
+    test_escaping!(
+        reasons_are_escaped_once,
+        Reason,
+        vec![
+            ("user's spam & eggs", "user&#x27;s spam &amp; eggs"),
+            ("user&#x27;s spam", "user&amp;#x27;s spam")
+        ]
+    );
+    test_input!(
+        unvalid_reasons,
+        Reason,
+        vec!["", "   ", &"spam ".repeat(60)],
+        false
+    );
+    test_input!(
+        valid_websites,
+        Website,
+        vec!["https://example.com", "http://example.com/~john?page=1"],
+        true
+    );
+    test_input!(
+        unvalid_websites,
+        Website,
+        vec![
+            "example.com",
+            "javascript:alert(1)",
+            "https://example.com/\"onclick=\"",
+            "https://"
+        ],
+        false
+    );
+    test_input!(
+        valid_timezones,
+        Timezone,
+        vec!["UTC", "Europe/Oslo", "America/Argentina/Buenos_Aires", "+01:00"],
+        true
+    );
+    test_input!(
+        unvalid_timezones,
+        Timezone,
+        vec!["", "oslo", "Europe/", "+1"],
+        false
+    );
+    test_input!(
+        valid_locales,
+        Locale,
+        vec!["en", "nb-NO", "zh-Hant-TW", "es-419"],
+        true
+    );
+    test_input!(
+        unvalid_locales,
+        Locale,
+        vec!["", "english", "en_US", "EN-us"],
+        false
+    );
     test_input!(
         valid_emails,
         Email,
         vec![
             "john.theme@example.com",
             "irene@google.no",
-            "post@tombarneby.com"
+            "post@tombarneby.com",
+            "a@mail.example.co.uk",
+            "john+forum@bücher.de",
+            " John@X.com "
         ],
         true
     );
     test_input!(
         unvalid_emails,
         Email,
-        vec!["john.theme.example.com", "irene@google", "tombarneby"],
+        vec![
+            "john.theme.example.com",
+            "irene@google",
+            "tombarneby",
+            "john..theme@example.com",
+            "a@[aaaaé]",
+            "a@[éa]"
+        ],
+        false
+    );
+
+    #[test]
+    fn normalized_emails() {
+        let email = |s: &str| Email::try_from(s.to_owned()).unwrap();
+        assert_eq!(email("John@X.com").normalized(), email("john@x.com").normalized());
+        assert_eq!(email("Post@Bücher.de").normalized(), "post@xn--bcher-kva.de");
+        assert_eq!(email(r#""a@b"@example.com"#).local_part(), r#""a@b""#);
+        assert!(email("john@Mailinator.com").is_disposable());
+        assert!(!email("john@example.com").is_disposable());
+    }
+
+    #[test]
+    fn emails_survive_serialization() {
+        for s in &["o'neil@x.com", "a&b@x.com", r#""a@b"@x.com"#, "post@bücher.de"] {
+            let email = Email::try_from(s.to_string()).unwrap();
+            let json = serde_json::to_string(&email).unwrap();
+            let parsed: Email = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, email);
+        }
+    }
+    test_input!(
+        valid_invite_codes,
+        InviteCode,
+        vec!["Xk3_9fQ-", " aB3dE5gH7jK9mN1p "],
+        true
+    );
+    test_input!(
+        unvalid_invite_codes,
+        InviteCode,
+        vec!["", "short", "with space code", "<script>alert</script>"],
         false
     );
     test_input!(
@@ -309,6 +1104,107 @@ mod tests {
         vec!["Hello everyone!", "Hello", "Hello, I love you all guys."],
         true
     );
+    test_input!(
+        valid_file_names,
+        FileName,
+        vec!["cat.png", "my report (final).pdf", ".hidden"],
+        true
+    );
+    test_input!(
+        unvalid_file_names,
+        FileName,
+        vec!["", "..", "../etc/passwd", "dir\\file.png", "new\nline.png"],
+        false
+    );
+    test_input!(
+        valid_emojis,
+        Emoji,
+        vec![":thumbsup:", ":+1:", "\u{1F44D}", "\u{1F44D}\u{1F3FD}"],
+        true
+    );
+    test_input!(
+        unvalid_emojis,
+        Emoji,
+        vec!["", "thumbsup", ":thumbs up:", "<b>", "\u{1F44D} "],
+        false
+    );
+    test_input!(
+        valid_tags,
+        TagName,
+        vec!["rust", "rust-lang", "Web", "c99"],
+        true
+    );
+    test_input!(
+        unvalid_tags,
+        TagName,
+        vec![
+            "a",
+            "-rust",
+            "rust-",
+            "rust lang",
+            "rust_lang",
+            "a-very-long-tag-name-indeed"
+        ],
+        false
+    );
+    test_input!(
+        valid_tag_prefixes,
+        TagPrefix,
+        vec!["r", "rust", "rust-", "Rust-L", " web "],
+        true
+    );
+    test_input!(
+        unvalid_tag_prefixes,
+        TagPrefix,
+        vec![
+            "",
+            "-",
+            "-rust",
+            "rust--",
+            "rust lang",
+            "rust_",
+            "a-very-long-tag-name-inde"
+        ],
+        false
+    );
+
+    #[test]
+    fn tag_list_limits() {
+        let tag = |s: &str| TagName::try_from(s.to_owned()).expect("invalid tag");
+        assert!(validate_tags(&[tag("rust"), tag("web")]).is_ok());
+        assert!(validate_tags(&[tag("rust"), tag("Rust")]).is_err());
+        let too_many: Vec<_> = (0..=MAX_TAGS_PER_THREAD)
+            .map(|i| tag(&format!("tag{}", i)))
+            .collect();
+        assert!(validate_tags(&too_many).is_err());
+    }
+
+    #[test]
+    fn poll_option_list_limits() {
+        let option = |s: &str| PollOption::try_from(s.to_owned()).expect("invalid poll option");
+        assert!(validate_poll_options(&[option("Yes"), option("No")]).is_ok());
+        assert!(validate_poll_options(&[option("Yes")]).is_err());
+        assert!(validate_poll_options(&[option("Yes"), option(" yes ")]).is_err());
+        let too_many: Vec<_> = (0..=MAX_POLL_OPTIONS)
+            .map(|i| option(&format!("Option {}", i)))
+            .collect();
+        assert!(validate_poll_options(&too_many).is_err());
+    }
+
+    #[test]
+    fn incomplete_title() {
+        let draft = Incomplete::<Title>::try_from(String::from("Hi"))
+            .expect("short drafts should be valid");
+        assert_eq!(draft.complete(), Err(ValidationError::InvalidTitle));
+
+        let draft = Incomplete::<Title>::try_from(String::from("A <complete> title"))
+            .expect("complete drafts should be valid");
+        assert_eq!(draft.complete().unwrap().to_string(), "A &lt;complete&gt; title");
+
+        let too_long = "a".repeat(MAX_DRAFT_LEN + 1);
+        assert!(Incomplete::<Description>::try_from(too_long).is_err());
+    }
+
     test_input!(
         valid_title,
         Title,
@@ -371,4 +1267,90 @@ mod tests {
             ("A comment with <script>", "A comment with &lt;script&gt;")
         ]
     );
+    test_escaping!(
+        entities_are_escaped_once,
+        Title,
+        vec![
+            ("A title &lt;3", "A title &amp;lt;3"),
+            ("Tom & Jerry &amp;", "Tom &amp; Jerry &amp;amp;")
+        ]
+    );
+
+    #[test]
+    fn confusable_usernames_are_the_same() {
+        let name = |s: &str| Username::try_from(s.to_owned()).unwrap();
+        assert!(name("Admin").is_confusable_with(&name("admin")));
+        assert!(name("adm1n").is_confusable_with(&name("ADMlN")));
+        assert!(name("mod_erator").is_confusable_with(&name("moderator")));
+        assert!(name("rnary").is_confusable_with(&name("mary")));
+        assert!(!name("john").is_confusable_with(&name("joan")));
+    }
+
+    #[test]
+    fn reserved_usernames() {
+        let name = |s: &str| Username::try_from(s.to_owned()).unwrap();
+        assert!(name("Adm1n").is_reserved());
+        assert!(name("r00t").is_reserved());
+        assert!(!name("admiral").is_reserved());
+        assert!(serde_json::from_str::<Username>("\"system\"").is_ok());
+
+        #[derive(Deserialize)]
+        struct New {
+            #[serde(deserialize_with = "deserialize_new_username")]
+            username: Username,
+        }
+        assert!(serde_json::from_str::<New>(r#"{"username":"Sy5tem"}"#).is_err());
+        let new: New = serde_json::from_str(r#"{"username":"john"}"#).unwrap();
+        assert_eq!(new.username, name("john"));
+    }
+
+    /// Serialize and deserialize the field, and check that it is unchanged
+    fn round_trip<T>(s: &str)
+    where
+        T: TryFrom<String> + serde::Serialize + serde::de::DeserializeOwned + Display,
+        T::Error: fmt::Debug,
+    {
+        let field = T::try_from(s.to_owned()).expect("invalid field");
+        let json = serde_json::to_string(&field).unwrap();
+        let parsed: T = serde_json::from_str(&json).unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        let parsed: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.to_string(), field.to_string());
+        assert_eq!(json, serde_json::to_string(s).unwrap());
+    }
+
+    #[test]
+    fn free_text_survives_serialization() {
+        let texts = [
+            "I &lt;3 <b>Rust</b>",
+            "Tom & Jerry's &amp;",
+            "&#x27; \"quoted\" &",
+        ];
+        for s in texts.iter() {
+            round_trip::<DisplayName>(s);
+            round_trip::<Title>(s);
+            round_trip::<Description>(s);
+            round_trip::<CommentContent>(s);
+            round_trip::<MessageContent>(s);
+            round_trip::<Location>(s);
+            round_trip::<PollOption>(s);
+        }
+    }
+
+    #[test]
+    fn file_names_survive_serialization() {
+        round_trip::<FileName>("Tom & Jerry's.png");
+        round_trip::<FileName>("&lt;b&gt;.txt");
+    }
+
+    #[test]
+    fn drafts_survive_serialization() {
+        let draft = Incomplete::<Title>::try_from(String::from("Tom & <Jerry>")).unwrap();
+        let json = serde_json::to_string(&draft).unwrap();
+        let parsed: Incomplete<Title> = serde_json::from_str(&json).unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        let parsed: Incomplete<Title> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, draft);
+        assert_eq!(&*parsed.complete().unwrap(), "Tom & <Jerry>");
+    }
 }
@@ -36,3 +36,96 @@ id_impls!(CommentId, CommentId => u32);
 #[serde(transparent)]
 pub struct UserId(u32);
 id_impls!(UserId, UserId => u32);
+
+impl UserId {
+    /// The tombstone user which replaces the author of content when a user
+    /// deletes their account
+    pub const DELETED: UserId = UserId(0);
+
+    /// Replace the id with the tombstone user if it is the deleted user
+    pub fn anonymize(&mut self, deleted: UserId) {
+        if *self == deleted {
+            *self = UserId::DELETED;
+        }
+    }
+}
+
+/// A direct referece to a specific report which is stored in the database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct ReportId(u32);
+id_impls!(ReportId, ReportId => u32);
+
+/// A direct referece to a specific private conversation which is stored in the
+/// database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct ConversationId(u32);
+id_impls!(ConversationId, ConversationId => u32);
+
+/// A direct referece to a specific private message which is stored in the
+/// database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct MessageId(u32);
+id_impls!(MessageId, MessageId => u32);
+
+/// A direct referece to a specific notification which is stored in the
+/// database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct NotificationId(u32);
+id_impls!(NotificationId, NotificationId => u32);
+
+/// A direct referece to a specific attachment which is stored in the database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct AttachmentId(u32);
+id_impls!(AttachmentId, AttachmentId => u32);
+
+/// A direct referece to a specific draft which is stored in the database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct DraftId(u32);
+id_impls!(DraftId, DraftId => u32);
+
+/// A direct referece to a specific sanction (ban, suspension or mute) of a
+/// user which is stored in the database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct SanctionId(u32);
+id_impls!(SanctionId, SanctionId => u32);
+
+/// A direct referece to a specific entry in the audit log which is stored in
+/// the database
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(transparent)]
+pub struct AuditEntryId(u32);
+id_impls!(AuditEntryId, AuditEntryId => u32);
+
+/// A direct reference to a piece of user generated content
+///
+/// Used by requests which can target either a thread or a comment, e.g. votes
+/// and reactions.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Debug, Hash)]
+#[serde(
+    tag = "type",
+    content = "id",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum ContentRef {
+    Thread(ThreadId),
+    Comment(CommentId),
+}
+
+impl From<ThreadId> for ContentRef {
+    fn from(id: ThreadId) -> Self {
+        ContentRef::Thread(id)
+    }
+}
+
+impl From<CommentId> for ContentRef {
+    fn from(id: CommentId) -> Self {
+        ContentRef::Comment(id)
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! These datatypes can be used to compose requests and responses
 
+pub mod email;
 pub mod fields;
 pub mod ids;
+pub mod network;
 pub mod token;
 
 // TODO update regexes or change validation to fit our need
@@ -15,8 +17,30 @@ const USERNAME_REGEX: &str = "^[a-zA-Z0-9_-]{4,10}$";
 /// The regex which vertifies that a password is formatted correctly
 const PASSWORD_REGEX: &str = "^[\\w\\d.@%$!]{8,64}$";
 
-/// The regex which vertifies that a password is formatted correctly
-const EMAIL_REGEX: &str = "^[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+\\.[A-Za-z]{2,}$";
+/// The regex which vertifies that a invite code is formatted correctly
+const INVITE_CODE_REGEX: &str = "^[A-Za-z0-9_-]{8,64}$";
+
+/// The regex which vertifies that a emoji shortcode (e.g. `:thumbsup:`) is
+/// formatted correctly
+const EMOJI_SHORTCODE_REGEX: &str = "^:[a-z0-9_+-]{1,32}:$";
+
+/// The regex which vertifies that a tag name is formatted correctly (a slug)
+const TAG_NAME_REGEX: &str = "^[a-z0-9]+(-[a-z0-9]+)*$";
+
+/// The regex which vertifies that the start of a tag name is formatted
+/// correctly (a slug which may end with a hyphen)
+const TAG_PREFIX_REGEX: &str = "^[a-z0-9]+(-[a-z0-9]+)*-?$";
+
+/// The regex which vertifies that a website url is formatted correctly
+const WEBSITE_REGEX: &str = r#"^https?://[^\s<>"']+$"#;
+
+/// The regex which vertifies that a timezone is formatted correctly, either
+/// as a IANA timezone name (e.g. `Europe/Oslo`) or as a fixed offset from UTC
+const TIMEZONE_REGEX: &str = r"^(UTC|[A-Z][A-Za-z_]+(/[A-Za-z0-9_+-]+){1,2}|[+-]\d{2}:\d{2})$";
+
+/// The regex which vertifies that a locale is formatted correctly (a subset
+/// of BCP 47, e.g. `nb-NO`)
+const LOCALE_REGEX: &str = "^[a-z]{2,3}(-[A-Z][a-z]{3})?(-([A-Z]{2}|[0-9]{3}))?$";
 
 /// The regex which vertifies that a search query is formatted correctly
 const SEARCH_QUERY_REGEX: &str = r"^([a-zA-Z0-9_-æøåÆØÅ]|\s){2,30}$";
@@ -25,6 +49,8 @@ const SEARCH_QUERY_REGEX: &str = r"^([a-zA-Z0-9_-æøåÆØÅ]|\s){2,30}$";
 pub enum ValidationError {
     #[fail(display = "invalid (badly formatted) username")]
     InvalidUsername,
+    #[fail(display = "username is reserved")]
+    ReservedUsername,
     #[fail(display = "invalid (badly formatted) password")]
     InvalidPassword,
     #[fail(display = "invalid (badly formatted) id")]
@@ -39,4 +65,46 @@ pub enum ValidationError {
     InvalidEmail,
     #[fail(display = "invalid (badly formatted) search query")]
     InvalidQuery,
+    #[fail(display = "invalid (badly formatted) emoji")]
+    InvalidEmoji,
+    #[fail(display = "invalid (badly formatted) tag")]
+    InvalidTag,
+    #[fail(display = "invalid (badly formatted) tag prefix")]
+    InvalidTagPrefix,
+    #[fail(display = "too many or duplicate tags")]
+    InvalidTagList,
+    #[fail(display = "too many items in a bulk request")]
+    TooManyBulkItems,
+    #[fail(display = "invalid (badly formatted) reason")]
+    InvalidReason,
+    #[fail(display = "invalid (badly formatted) message")]
+    InvalidMessageContent,
+    #[fail(display = "too many users mentioned")]
+    TooManyMentions,
+    #[fail(display = "invalid (badly formatted) file name")]
+    InvalidFileName,
+    #[fail(display = "invalid (badly formatted) poll option")]
+    InvalidPollOption,
+    #[fail(display = "too few, too many or duplicate poll options")]
+    InvalidPollOptionList,
+    #[fail(display = "draft is too long")]
+    InvalidDraft,
+    #[fail(display = "invalid (badly formatted) display name")]
+    InvalidDisplayName,
+    #[fail(display = "invalid (badly formatted) location")]
+    InvalidLocation,
+    #[fail(display = "invalid (badly formatted) website")]
+    InvalidWebsite,
+    #[fail(display = "invalid (badly formatted) timezone")]
+    InvalidTimezone,
+    #[fail(display = "invalid (badly formatted) locale")]
+    InvalidLocale,
+    #[fail(display = "invalid number of comments per page")]
+    InvalidCommentsPerPage,
+    #[fail(display = "too many unknown settings")]
+    TooManyUnknownSettings,
+    #[fail(display = "invalid (badly formatted) ip network")]
+    InvalidIpNetwork,
+    #[fail(display = "invalid (badly formatted) invite code")]
+    InvalidInviteCode,
 }
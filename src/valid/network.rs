@@ -0,0 +1,207 @@
+//! Validated IP networks in CIDR notation
+
+use super::ValidationError;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// A valid IPv4 or IPv6 network in CIDR notation (e.g. `10.0.0.0/8`)
+///
+/// A single address without a prefix length is a network of only that
+/// address. Any bits of the address outside of the prefix are cleared, so
+/// `10.1.2.3/8` is the same network as `10.0.0.0/8`.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone, Copy, Hash)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+/// Get the bits of an address together with the number of bits
+fn to_bits(addr: IpAddr) -> (u128, u8) {
+    match addr {
+        IpAddr::V4(v4) => (u128::from(u32::from(v4)), 32),
+        IpAddr::V6(v6) => (u128::from(v6), 128),
+    }
+}
+
+/// Convert a IPv4-mapped IPv6 address (e.g. `::ffff:10.0.0.1`) to the IPv4
+/// address it represents, other addresses are returned as is
+///
+/// A client connecting to a dual stack socket over IPv4 shows up with a
+/// mapped address, so addresses must be canonicalized before they are
+/// compared with IPv4 networks.
+pub fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => {
+                IpAddr::V4(Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo)))
+            }
+            _ => addr,
+        },
+        IpAddr::V4(_) => addr,
+    }
+}
+
+/// Get a mask of the first `prefix` bits of an address with `width` bits
+fn mask(prefix: u8, width: u8) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        (!0u128 << (128 - u32::from(prefix))) >> (128 - u32::from(width))
+    }
+}
+
+impl IpNetwork {
+    /// Create a new network, fails if the prefix is longer than the address
+    ///
+    /// Networks of IPv4-mapped IPv6 addresses are converted to the IPv4
+    /// network they represent.
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Self, ValidationError> {
+        let (_, width) = to_bits(addr);
+        if prefix > width {
+            return Err(ValidationError::InvalidIpNetwork);
+        }
+        let (addr, prefix) = match canonical(addr) {
+            IpAddr::V4(v4) if addr.is_ipv6() && prefix >= 96 => (IpAddr::V4(v4), prefix - 96),
+            _ => (addr, prefix),
+        };
+        let (bits, width) = to_bits(addr);
+        let bits = bits & mask(prefix, width);
+        let addr = match addr {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(bits as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(bits)),
+        };
+        Ok(IpNetwork { addr, prefix })
+    }
+
+    /// The first address of the network
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The number of leading bits which are fixed in the network
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Check if the address (or the IPv4 address it maps to) is within the
+    /// network
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        let (network_bits, width) = to_bits(self.addr);
+        let (bits, addr_width) = to_bits(canonical(addr));
+        width == addr_width && bits & mask(self.prefix, width) == network_bits
+    }
+
+    /// Get the bit at `index` of the network address, counted from the most
+    /// significant bit
+    pub(crate) fn bit(&self, index: u8) -> bool {
+        bit(self.addr, index)
+    }
+}
+
+/// Get the bit at `index` of an address, counted from the most significant
+/// bit
+pub(crate) fn bit(addr: IpAddr, index: u8) -> bool {
+    let (bits, width) = to_bits(addr);
+    (bits >> (width - 1 - index)) & 1 == 1
+}
+
+impl From<IpAddr> for IpNetwork {
+    fn from(addr: IpAddr) -> Self {
+        let addr = canonical(addr);
+        let (_, width) = to_bits(addr);
+        IpNetwork {
+            addr,
+            prefix: width,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for IpNetwork {
+    type Error = ValidationError;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let mut parts = s.splitn(2, '/');
+        let addr: IpAddr = parts
+            .next()
+            .and_then(|addr| addr.parse().ok())
+            .ok_or(ValidationError::InvalidIpNetwork)?;
+        match parts.next() {
+            Some(prefix) => {
+                let prefix = prefix
+                    .parse()
+                    .map_err(|_| ValidationError::InvalidIpNetwork)?;
+                IpNetwork::new(addr, prefix)
+            }
+            None => Ok(IpNetwork::from(addr)),
+        }
+    }
+}
+
+impl TryFrom<String> for IpNetwork {
+    type Error = ValidationError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        IpNetwork::try_from(s.as_str())
+    }
+}
+
+impl_deserialize_with_try_from!(IpNetwork);
+
+impl serde::Serialize for IpNetwork {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(s: &str) -> IpNetwork {
+        IpNetwork::try_from(s).expect("invalid network")
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().expect("invalid ip")
+    }
+
+    #[test]
+    fn parses_and_normalizes() {
+        assert_eq!(network("10.1.2.3/8").to_string(), "10.0.0.0/8");
+        assert_eq!(network("10.1.2.3").to_string(), "10.1.2.3/32");
+        assert_eq!(network("0.0.0.0/0").to_string(), "0.0.0.0/0");
+        assert_eq!(network("2001:db8::1/32").to_string(), "2001:db8::/32");
+        assert_eq!(network("::/0").to_string(), "::/0");
+        for s in ["", "10.0.0.0/33", "::/129", "10.0.0/8", "10.0.0.0/", "10.0.0.0/-1"].iter() {
+            assert!(IpNetwork::try_from(*s).is_err(), "expected '{}' to be invalid", s);
+        }
+    }
+
+    #[test]
+    fn contains_addresses() {
+        assert!(network("10.0.0.0/8").contains(ip("10.255.0.1")));
+        assert!(!network("10.0.0.0/8").contains(ip("11.0.0.1")));
+        assert!(network("0.0.0.0/0").contains(ip("192.168.0.1")));
+        assert!(!network("0.0.0.0/0").contains(ip("::1")));
+        assert!(network("2001:db8::/32").contains(ip("2001:db8:ffff::1")));
+        assert!(!network("2001:db8::/32").contains(ip("2001:db9::1")));
+    }
+
+    #[test]
+    fn canonicalizes_mapped_addresses() {
+        assert!(network("10.0.0.0/8").contains(ip("::ffff:10.0.0.1")));
+        assert!(!network("10.0.0.0/8").contains(ip("::ffff:11.0.0.1")));
+        assert_eq!(network("::ffff:10.1.2.3/104").to_string(), "10.0.0.0/8");
+        assert_eq!(network("::ffff:10.1.2.3").to_string(), "10.1.2.3/32");
+        assert_eq!(network("::ffff:0:0/95").to_string(), "::fffe:0:0/95");
+        assert_eq!(canonical(ip("::1")), ip("::1"));
+    }
+}